@@ -1,7 +1,7 @@
 use std::any::type_name;
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use importunate::{inner::Inner, *};
+use importunate::{byte_shuffle::ByteShuffle, inner::Inner, *};
 
 pub fn criterion_benchmark(c: &mut Criterion) {
     bench_new_index::<u128, 34>(c, 0);
@@ -34,6 +34,12 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     bench_calculate::<u64, 20>(c);
     bench_calculate::<u128, 34>(c);
 
+    bench_calculate_rotated::<u8, 5>(c);
+    bench_calculate_rotated::<u16, 8>(c);
+    bench_calculate_rotated::<u32, 12>(c);
+    bench_calculate_rotated::<u64, 20>(c);
+    bench_calculate_rotated::<u128, 34>(c);
+
     bench_apply::<u8, 5>(c);
     bench_apply::<u128, 5>(c);
     bench_apply::<u16, 8>(c);
@@ -41,6 +47,14 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     bench_apply::<u64, 15>(c);
     bench_apply::<u64, 20>(c);
     bench_apply::<u128, 34>(c);
+
+    bench_apply::<u64, 16>(c);
+    bench_apply::<u128, 32>(c);
+
+    bench_byte_shuffle::<u8, 5>(c);
+    bench_byte_shuffle::<u32, 12>(c);
+    bench_byte_shuffle::<u64, 16>(c);
+    bench_byte_shuffle::<u128, 32>(c);
 }
 
 fn bench_old_index<I: Inner, const SIZE: usize>(c: &mut Criterion, index: u8) {
@@ -67,6 +81,19 @@ fn bench_apply<I: Inner, const SIZE: usize>(c: &mut Criterion) {
         b.iter(|| apply(black_box(test_arr), perm))
     });
 }
+fn bench_byte_shuffle<I: Inner, const SIZE: usize>(c: &mut Criterion) {
+    c.bench_function(
+        format!("byte_shuffle {} {SIZE}", type_name::<I>()).as_str(),
+        |b| {
+            let arr = Permutation::<I, SIZE>::get_last().get_array();
+            let shuffle =
+                ByteShuffle::from(Permutation::<I, SIZE>::try_calculate(arr, |&x| x).unwrap());
+            let test_arr = arr;
+            b.iter(|| byte_shuffle(black_box(test_arr), &shuffle))
+        },
+    );
+}
+
 fn bench_calculate<I: Inner, const SIZE: usize>(c: &mut Criterion) {
     c.bench_function(
         format!("calculate {} {SIZE}", type_name::<I>()).as_str(),
@@ -79,6 +106,18 @@ fn bench_calculate<I: Inner, const SIZE: usize>(c: &mut Criterion) {
     );
 }
 
+fn bench_calculate_rotated<I: Inner, const SIZE: usize>(c: &mut Criterion) {
+    c.bench_function(
+        format!("calculate_rotated {} {SIZE}", type_name::<I>()).as_str(),
+        |b| {
+            let mut arr: [u8; SIZE] = Permutation::<I, SIZE>::default().get_array();
+            arr.rotate_left(SIZE / 3);
+            let test_arr = arr;
+            b.iter(|| calculate::<I, SIZE>(black_box(test_arr)))
+        },
+    );
+}
+
 fn bench_combine_medium_case<I: Inner, const SIZE: usize>(c: &mut Criterion) {
     c.bench_function(
         format!("combine_medium {} {SIZE}", type_name::<I>()).as_str(),
@@ -101,7 +140,7 @@ fn bench_combine_worst_case<I: Inner, const SIZE: usize>(c: &mut Criterion) {
     );
 }
 
-fn calculate<I: Inner, const SIZE: usize>(arr: [u8; SIZE]) -> Permutation<u64, SIZE> {
+fn calculate<I: Inner, const SIZE: usize>(arr: [u8; SIZE]) -> Permutation<I, SIZE> {
     Permutation::calculate_unchecked(arr, |&x| x)
 }
 
@@ -113,6 +152,11 @@ fn apply<I: Inner, const SIZE: usize>(
     arr
 }
 
+fn byte_shuffle<const SIZE: usize>(mut arr: [u8; SIZE], shuffle: &ByteShuffle<SIZE>) -> [u8; SIZE] {
+    shuffle.apply(&mut arr);
+    arr
+}
+
 fn new_index<I: Inner, const SIZE: usize>(permutation: Permutation<I, SIZE>, index: u8) -> u8 {
     permutation.index_of(&index, |&x| x)
 }
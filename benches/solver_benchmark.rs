@@ -0,0 +1,42 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use importunate::{solver::SolveContext, *};
+
+type Perm = Permutation<u32, 9>;
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let swaps = (1..9)
+        .map(|i| {
+            let mut arr = Perm::default().get_array();
+            arr.swap(0, i);
+            Perm::calculate_unchecked(arr, |&x| x)
+        })
+        .collect();
+    bench_solve_context(c, "swaps", swaps);
+
+    // Three cycles only generate the even permutations, so half of the table cannot be solved
+    let three_cycles = (2..9)
+        .map(|i| {
+            let mut arr = Perm::default().get_array();
+            arr[0] = 1;
+            arr[1] = i as u8;
+            arr[i] = 0;
+            Perm::calculate_unchecked(arr, |&x| x)
+        })
+        .collect();
+    bench_solve_context(c, "three_cycles", three_cycles);
+}
+
+fn bench_solve_context(c: &mut Criterion, name: &str, moves: Vec<Perm>) {
+    let mut group = c.benchmark_group("solve_context");
+    group.sample_size(10);
+    group.bench_function(format!("new {name} 9").as_str(), |b| {
+        b.iter(|| SolveContext::new(black_box(moves.clone())))
+    });
+    group.bench_function(format!("new_parallel {name} 9").as_str(), |b| {
+        b.iter(|| SolveContext::new_parallel(black_box(moves.clone())))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,213 @@
+use crate::{inner::Inner, Permutation};
+
+/// A permutation prepared for reordering arrays of bytes.
+/// On `x86_64` processors this uses a single `pshufb` instruction for up to 16 bytes (with SSSE3),
+/// or a pair of `vpshufb` instructions for up to 32 bytes (with AVX2).
+/// Otherwise the bytes are reordered one at a time.
+///
+/// Creating this does the work of decoding the permutation, so keep it around if you are going to reorder many arrays.
+/// ```
+/// use importunate::{Permutation, byte_shuffle::ByteShuffle};
+/// let perm = Permutation::<u8, 4>::calculate_unchecked([2, 0, 1, 3], |&x| x);
+/// let shuffle = ByteShuffle::from(perm);
+/// let mut bytes = *b"abcd";
+/// shuffle.apply(&mut bytes);
+/// assert_eq!(&bytes, b"cabd");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct ByteShuffle<const ELEMENTS: usize> {
+    /// The old index of the byte at each index
+    indices: [u8; ELEMENTS],
+    /// The old index of each byte within its 128 bit lane, padded to 32 bytes
+    lane_indices: [u8; 32],
+    /// `0x80` for each byte which comes from the other 128 bit lane
+    cross_lane: [u8; 32],
+}
+
+impl<I: Inner, const ELEMENTS: usize> From<Permutation<I, ELEMENTS>> for ByteShuffle<ELEMENTS> {
+    fn from(permutation: Permutation<I, ELEMENTS>) -> Self {
+        Self::new(permutation.get_array())
+    }
+}
+
+impl<const ELEMENTS: usize> ByteShuffle<ELEMENTS> {
+    pub(crate) fn new(indices: [u8; ELEMENTS]) -> Self {
+        let mut lane_indices = [0; 32];
+        let mut cross_lane = [0; 32];
+        for (index, (lane_index, cross)) in lane_indices
+            .iter_mut()
+            .zip(cross_lane.iter_mut())
+            .enumerate()
+        {
+            let old_index = indices.get(index).copied().unwrap_or(index as u8);
+            *lane_index = old_index & 0b1111;
+            if (old_index >> 4) != (index as u8 >> 4) {
+                *cross = 0x80;
+            }
+        }
+
+        Self {
+            indices,
+            lane_indices,
+            cross_lane,
+        }
+    }
+
+    /// The old index of the byte at each index
+    pub(crate) fn indices(&self) -> &[u8; ELEMENTS] {
+        &self.indices
+    }
+
+    /// Reorder the bytes
+    pub fn apply(&self, bytes: &mut [u8; ELEMENTS]) {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if ELEMENTS <= 16 && x86::has_ssse3() {
+                // SAFETY: we have just checked that SSSE3 is available
+                unsafe { x86::apply_ssse3(self, bytes) };
+                return;
+            }
+            if ELEMENTS <= 32 && x86::has_avx2() {
+                // SAFETY: we have just checked that AVX2 is available
+                unsafe { x86::apply_avx2(self, bytes) };
+                return;
+            }
+        }
+
+        self.apply_scalar(bytes);
+    }
+
+    fn apply_scalar(&self, bytes: &mut [u8; ELEMENTS]) {
+        let old = *bytes;
+        for (byte, &old_index) in bytes.iter_mut().zip(self.indices.iter()) {
+            *byte = old[usize::from(old_index)];
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use core::arch::x86_64::{
+        _mm256_blendv_epi8, _mm256_loadu_si256, _mm256_permute2x128_si256, _mm256_shuffle_epi8,
+        _mm256_storeu_si256, _mm_loadu_si128, _mm_shuffle_epi8, _mm_storeu_si128,
+    };
+
+    use super::ByteShuffle;
+
+    pub fn has_ssse3() -> bool {
+        #[cfg(target_feature = "ssse3")]
+        {
+            true
+        }
+        #[cfg(all(not(target_feature = "ssse3"), any(test, feature = "std")))]
+        {
+            std::is_x86_feature_detected!("ssse3")
+        }
+        #[cfg(all(not(target_feature = "ssse3"), not(any(test, feature = "std"))))]
+        {
+            false
+        }
+    }
+
+    pub fn has_avx2() -> bool {
+        #[cfg(target_feature = "avx2")]
+        {
+            true
+        }
+        #[cfg(all(not(target_feature = "avx2"), any(test, feature = "std")))]
+        {
+            std::is_x86_feature_detected!("avx2")
+        }
+        #[cfg(all(not(target_feature = "avx2"), not(any(test, feature = "std"))))]
+        {
+            false
+        }
+    }
+
+    /// Reorder up to 16 bytes with `pshufb`
+    #[target_feature(enable = "ssse3")]
+    pub unsafe fn apply_ssse3<const ELEMENTS: usize>(
+        shuffle: &ByteShuffle<ELEMENTS>,
+        bytes: &mut [u8; ELEMENTS],
+    ) {
+        let mut data = [0u8; 16];
+        data[..ELEMENTS].copy_from_slice(bytes);
+
+        let data_vec = _mm_loadu_si128(data.as_ptr().cast());
+        let indices = _mm_loadu_si128(shuffle.lane_indices.as_ptr().cast());
+        _mm_storeu_si128(
+            data.as_mut_ptr().cast(),
+            _mm_shuffle_epi8(data_vec, indices),
+        );
+
+        bytes.copy_from_slice(&data[..ELEMENTS]);
+    }
+
+    /// Reorder up to 32 bytes with `vpshufb`.
+    /// This only shuffles within 128 bit lanes, so shuffle both the bytes and the bytes with their lanes swapped, then blend the results.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn apply_avx2<const ELEMENTS: usize>(
+        shuffle: &ByteShuffle<ELEMENTS>,
+        bytes: &mut [u8; ELEMENTS],
+    ) {
+        let mut data = [0u8; 32];
+        data[..ELEMENTS].copy_from_slice(bytes);
+
+        let data_vec = _mm256_loadu_si256(data.as_ptr().cast());
+        let swapped = _mm256_permute2x128_si256(data_vec, data_vec, 0x01);
+        let indices = _mm256_loadu_si256(shuffle.lane_indices.as_ptr().cast());
+        let cross_lane = _mm256_loadu_si256(shuffle.cross_lane.as_ptr().cast());
+
+        let same_lane = _mm256_shuffle_epi8(data_vec, indices);
+        let other_lane = _mm256_shuffle_epi8(swapped, indices);
+        let result = _mm256_blendv_epi8(same_lane, other_lane, cross_lane);
+        _mm256_storeu_si256(data.as_mut_ptr().cast(), result);
+
+        bytes.copy_from_slice(&data[..ELEMENTS]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbtest::arbtest;
+
+    use super::ByteShuffle;
+    use crate::{inner::Inner, Permutation};
+
+    fn check<I: Inner, const ELEMENTS: usize>(perm: Permutation<I, ELEMENTS>) {
+        let mut expected = [0u8; ELEMENTS];
+        for (i, x) in expected.iter_mut().enumerate() {
+            *x = (i as u8) * 3 + 1;
+        }
+        let original = expected;
+        perm.apply(&mut expected);
+
+        let mut actual = original;
+        ByteShuffle::from(perm).apply(&mut actual);
+        assert_eq!(actual, expected);
+
+        let mut scalar = original;
+        ByteShuffle::from(perm).apply_scalar(&mut scalar);
+        assert_eq!(scalar, expected);
+    }
+
+    #[test]
+    pub fn test_byte_shuffle_all() {
+        for perm in Permutation::<u8, 5>::all() {
+            check(perm);
+        }
+    }
+
+    #[test]
+    pub fn test_byte_shuffle_arbitrary() {
+        arbtest(|u| {
+            check(u.arbitrary::<Permutation<u64, 16>>()?);
+            check(u.arbitrary::<Permutation<u64, 17>>()?);
+            check(u.arbitrary::<Permutation<u128, 32>>()?);
+            check(u.arbitrary::<Permutation<u128, 34>>()?);
+            check(Permutation::<u128, 32>::reverse());
+            Ok(())
+        });
+    }
+}
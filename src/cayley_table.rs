@@ -0,0 +1,108 @@
+use alloc::vec::Vec;
+
+use crate::{inner::Inner, Permutation};
+
+/// Precalculated products and inverses of every permutation of a small number of elements.
+/// Combining and inverting permutations using the table is a single lookup.
+///
+/// The table holds `n! * n!` permutations so it is only suitable for small numbers of elements.
+/// For 5 elements it takes 14KB, for 6 elements it takes 1MB and for 7 elements it takes 50MB.
+/// ```
+/// use importunate::{Permutation, cayley_table::CayleyTable};
+/// let table = CayleyTable::<u8, 4>::new();
+/// let lhs = Permutation::<u8, 4>::rotate_right();
+/// let rhs = Permutation::<u8, 4>::reverse();
+///
+/// assert_eq!(table.combine(&lhs, &rhs), lhs.combine(&rhs));
+/// assert_eq!(table.invert(&lhs), lhs.invert());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct CayleyTable<I: Inner, const ELEMENTS: usize> {
+    /// The product of `lhs` and `rhs` is at index `lhs * n! + rhs`
+    products: Vec<Permutation<I, ELEMENTS>>,
+    inverses: Vec<Permutation<I, ELEMENTS>>,
+}
+
+impl<I: Inner, const ELEMENTS: usize> Default for CayleyTable<I, ELEMENTS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize> CayleyTable<I, ELEMENTS> {
+    /// Calculate the table
+    /// # Panics
+    ///
+    /// This will panic if the table would have more than `usize::MAX` entries
+    pub fn new() -> Self {
+        let len = Permutation::<I, ELEMENTS>::table_len();
+        let Some(total) = len.checked_mul(len) else {
+            panic!("Cannot make a table for {ELEMENTS} elements!");
+        };
+
+        let mut products = Vec::with_capacity(total);
+        for lhs in Permutation::<I, ELEMENTS>::all() {
+            for rhs in Permutation::<I, ELEMENTS>::all() {
+                products.push(lhs.combine(&rhs));
+            }
+        }
+        let inverses = Permutation::<I, ELEMENTS>::all()
+            .map(|perm| perm.invert())
+            .collect();
+
+        Self { products, inverses }
+    }
+
+    /// Combine two permutations. Equivalent to `lhs.combine(rhs)`
+    pub fn combine(
+        &self,
+        lhs: &Permutation<I, ELEMENTS>,
+        rhs: &Permutation<I, ELEMENTS>,
+    ) -> Permutation<I, ELEMENTS> {
+        self.products[lhs.table_index() * self.inverses.len() + rhs.table_index()]
+    }
+
+    /// Invert a permutation. Equivalent to `perm.invert()`
+    pub fn invert(&self, perm: &Permutation<I, ELEMENTS>) -> Permutation<I, ELEMENTS> {
+        self.inverses[perm.table_index()]
+    }
+
+    /// Generate the cycle with this permutation as the operator. Equivalent to `perm.generate_cycle()`
+    pub fn generate_cycle(
+        &self,
+        perm: Permutation<I, ELEMENTS>,
+    ) -> impl Iterator<Item = Permutation<I, ELEMENTS>> + '_ {
+        core::iter::successors(Some(perm), move |next| {
+            if next.is_default() {
+                None
+            } else {
+                Some(self.combine(next, &perm))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::CayleyTable;
+    use crate::Permutation;
+
+    #[test]
+    pub fn test_table() {
+        type Perm = Permutation<u8, 4>;
+        let table = CayleyTable::<u8, 4>::new();
+        for lhs in Perm::all() {
+            assert_eq!(table.invert(&lhs), lhs.invert());
+            assert_eq!(
+                table.generate_cycle(lhs).collect_vec(),
+                lhs.generate_cycle().collect_vec()
+            );
+            for rhs in Perm::all() {
+                assert_eq!(table.combine(&lhs, &rhs), lhs.combine(&rhs));
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{inner::Inner, Permutation};
+
+/// Can be used to solve a permutation with moves which cannot be undone,
+/// finding the shortest combination of a fixed set of moves that leads to it.
+///
+/// Unlike a `SolveContext` this does not use the inverses of the moves.
+/// Without the inverses, a move can take a permutation arbitrarily further from solved,
+/// so this stores the exact distance of each permutation and takes eight times the memory of a `SolveContext`.
+/// ```
+/// use importunate::{Permutation, directed_solver::DirectedSolveContext};
+/// let rotate = Permutation::<u8, 5>::rotate_right();
+/// let context = DirectedSolveContext::new(vec![rotate]);
+///
+/// // Undoing a rotation takes four more rotations
+/// assert_eq!(context.solve(rotate), Some(vec![rotate; 4]));
+/// assert_eq!(context.distance(rotate.invert()), Some(1));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirectedSolveContext<I: Inner, const ELEMENTS: usize> {
+    /// The number of moves needed to solve each permutation, or `u16::MAX` if it cannot be solved
+    distances: Vec<u16>,
+
+    /// The total number of solvable permutations
+    pub number_solvable: I,
+
+    moves: Vec<Permutation<I, ELEMENTS>>,
+}
+
+impl<I: Inner, const ELEMENTS: usize> DirectedSolveContext<I, ELEMENTS> {
+    /// Create a new solver from a fixed set of moves. This will not use the inverses of those moves
+    /// # Panics
+    ///
+    /// This will panic if the number of possible solutions is greater than `usize::MAX`,
+    /// or if any permutation needs `u16::MAX` or more moves to solve
+    #[must_use]
+    pub fn new(mut moves: Vec<Permutation<I, ELEMENTS>>) -> Self {
+        let total = Permutation::<I, ELEMENTS>::table_len();
+        moves.sort();
+        moves.dedup();
+
+        // Search backwards from solved: the permutations one move before `perm` are `perm` combined with an inverse move
+        let inverse_moves: Vec<_> = moves.iter().map(Permutation::invert).collect();
+        let mut distances = vec![u16::MAX; total];
+        distances[Permutation::<I, ELEMENTS>::default().table_index()] = 0;
+        let mut number_solvable = 1usize;
+
+        // Each permutation is set when it is first reached, so the frontiers never hold duplicates
+        let mut distance = 0u16;
+        let mut current = vec![Permutation::<I, ELEMENTS>::default()];
+        let mut next = vec![];
+        while !current.is_empty() {
+            for perm in current.drain(..) {
+                for inverse in &inverse_moves {
+                    let previous = perm.combine(inverse);
+                    let index = previous.table_index();
+                    if distances[index] == u16::MAX {
+                        assert!(
+                            distance + 1 < u16::MAX,
+                            "Permutations need too many moves to solve"
+                        );
+                        distances[index] = distance + 1;
+                        number_solvable += 1;
+                        next.push(previous);
+                    }
+                }
+            }
+
+            core::mem::swap(&mut current, &mut next);
+            distance += 1;
+        }
+
+        Self {
+            distances,
+            number_solvable: I::try_from(number_solvable).unwrap_or_else(|_| unreachable!()),
+            moves,
+        }
+    }
+
+    /// The moves this solver uses
+    pub fn moves(&self) -> &[Permutation<I, ELEMENTS>] {
+        &self.moves
+    }
+
+    /// The number of moves needed to solve this permutation, or `None` if it cannot be solved
+    #[must_use]
+    pub fn distance(&self, perm: Permutation<I, ELEMENTS>) -> Option<usize> {
+        let distance = self.distances[perm.table_index()];
+        (distance != u16::MAX).then_some(usize::from(distance))
+    }
+
+    /// Try to Deconstruct the inverse of this permutation into the shortest sequence of moves.
+    /// The moves are in the order they should be performed
+    pub fn solve(
+        &self,
+        mut perm: Permutation<I, ELEMENTS>,
+    ) -> Option<Vec<Permutation<I, ELEMENTS>>> {
+        let mut distance = self.distance(perm)?;
+        let mut result = vec![];
+
+        'outer: while distance > 0 {
+            for m in &self.moves {
+                let next = perm.combine(m);
+                if self.distance(next) == Some(distance - 1) {
+                    perm = next;
+                    result.push(*m);
+                    distance -= 1;
+                    continue 'outer;
+                }
+            }
+
+            unreachable!()
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, VecDeque};
+
+    use super::DirectedSolveContext;
+    use crate::{solver::SolveContext, Permutation};
+
+    type Perm = Permutation<u8, 5>;
+
+    /// Find the distance of every permutation by searching forwards from each one
+    fn forward_distance(perm: Perm, moves: &[Perm]) -> Option<usize> {
+        let mut seen = HashMap::from([(perm, 0)]);
+        let mut queue = VecDeque::from([perm]);
+        while let Some(p) = queue.pop_front() {
+            let distance = seen[&p];
+            if p.is_default() {
+                return Some(distance);
+            }
+            for m in moves {
+                seen.entry(p.combine(m)).or_insert_with(|| {
+                    queue.push_back(p.combine(m));
+                    distance + 1
+                });
+            }
+        }
+        None
+    }
+
+    #[test]
+    pub fn test_directed() {
+        let three_cycle = Perm::calculate_unchecked([1, 2, 0, 3, 4], |&x| x);
+        for moves in [
+            vec![Perm::rotate_right()],
+            vec![Perm::rotate_right(), Perm::interleave(2)],
+            vec![Perm::rotate_left(), three_cycle],
+        ] {
+            let context = DirectedSolveContext::new(moves.clone());
+            let mut solvable = 0;
+            for perm in Perm::all() {
+                let distance = context.distance(perm);
+                assert_eq!(distance, forward_distance(perm, &moves));
+
+                let solution = context.solve(perm);
+                assert_eq!(solution.as_ref().map(Vec::len), distance);
+                if let Some(solution) = solution {
+                    assert!(solution.iter().all(|m| moves.contains(m)));
+                    assert!(solution.iter().fold(perm, |p, m| p.combine(m)).is_default());
+                    solvable += 1;
+                }
+            }
+            assert_eq!(usize::from(context.number_solvable), solvable);
+        }
+    }
+
+    #[test]
+    pub fn test_undirected() {
+        let moves = vec![Perm::rotate_right(), Perm::rotate_left(), Perm::reverse()];
+        let directed = DirectedSolveContext::new(moves.clone());
+        let undirected = SolveContext::new(moves);
+        assert_eq!(directed.number_solvable, undirected.number_solvable);
+        for perm in Perm::all() {
+            assert_eq!(directed.distance(perm), undirected.distance(perm));
+        }
+    }
+}
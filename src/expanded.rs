@@ -0,0 +1,206 @@
+use crate::{byte_shuffle::ByteShuffle, inner::Inner, permutable::Permutable, Permutation};
+
+/// A permutation with its array, inverse array and swaps already calculated.
+/// This takes more space than a `Permutation` but finding elements and indices takes constant time and applying it does no division.
+/// Use this when applying the same permutation many times.
+/// ```
+/// use importunate::{Permutation, expanded::ExpandedPermutation};
+/// let perm = Permutation::<u8, 4>::calculate_unchecked([2, 0, 1, 3], |&x| x);
+/// let expanded = ExpandedPermutation::from(perm);
+///
+/// assert_eq!(expanded.element_at_index(0, |x| x), 2);
+/// assert_eq!(expanded.index_of(&2, |&x| x), 0);
+///
+/// let mut arr = ["zero", "one", "two", "three"];
+/// expanded.apply(&mut arr);
+/// assert_eq!(arr, ["two", "zero", "one", "three"]);
+///
+/// assert_eq!(Permutation::<u8, 4>::from(expanded), perm);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct ExpandedPermutation<const ELEMENTS: usize> {
+    /// The old index of the element at each index, prepared for shuffling bytes
+    shuffle: ByteShuffle<ELEMENTS>,
+    /// The new index of each element
+    inverse: [u8; ELEMENTS],
+    swaps: [u8; ELEMENTS],
+}
+
+impl<I: Inner, const ELEMENTS: usize> From<Permutation<I, ELEMENTS>>
+    for ExpandedPermutation<ELEMENTS>
+{
+    fn from(permutation: Permutation<I, ELEMENTS>) -> Self {
+        Self::from_swaps(permutation.swaps_array())
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize> From<ExpandedPermutation<ELEMENTS>>
+    for Permutation<I, ELEMENTS>
+{
+    fn from(expanded: ExpandedPermutation<ELEMENTS>) -> Self {
+        debug_assert!(ELEMENTS <= I::MAX_ELEMENTS);
+        Self::from_swaps(expanded.swaps.into_iter())
+    }
+}
+
+impl<const ELEMENTS: usize> Default for ExpandedPermutation<ELEMENTS> {
+    fn default() -> Self {
+        Self::from_swaps([0; ELEMENTS])
+    }
+}
+
+impl<const ELEMENTS: usize> ExpandedPermutation<ELEMENTS> {
+    fn from_swaps(swaps: [u8; ELEMENTS]) -> Self {
+        let mut array = [0; ELEMENTS];
+        for (index, element) in array.iter_mut().enumerate() {
+            *element = index as u8;
+        }
+        for (index, &swap) in swaps.iter().enumerate() {
+            array.swap(index, index + usize::from(swap));
+        }
+
+        let mut inverse = [0; ELEMENTS];
+        for (index, &element) in array.iter().enumerate() {
+            inverse[usize::from(element)] = index as u8;
+        }
+
+        Self {
+            shuffle: ByteShuffle::new(array),
+            inverse,
+            swaps,
+        }
+    }
+
+    /// The complete array of this permutation's elements
+    #[must_use]
+    pub fn array(&self) -> &[u8; ELEMENTS] {
+        self.shuffle.indices()
+    }
+
+    /// The new index of each element
+    #[must_use]
+    pub fn inverse_array(&self) -> &[u8; ELEMENTS] {
+        &self.inverse
+    }
+
+    /// The swaps represented by this permutation
+    #[must_use]
+    pub fn swaps_array(&self) -> &[u8; ELEMENTS] {
+        &self.swaps
+    }
+
+    /// Is this the default permutation which does not reorder elements
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        self.swaps.iter().all(|&x| x == 0)
+    }
+
+    /// Get the element at the given index of the permutation
+    pub fn element_at_index<T, F: Fn(u8) -> T>(&self, new_index: u8, f: F) -> T {
+        f(self.array()[usize::from(new_index)])
+    }
+
+    /// Get the new index of the given element from the permutation
+    pub fn index_of<T, F: Fn(&T) -> u8>(&self, element: &T, f: F) -> u8 {
+        self.inverse[usize::from(f(element))]
+    }
+
+    /// Apply this permutation to an array, reordering the first `ELEMENTS` elements
+    pub fn apply<T>(&self, arr: &mut [T]) {
+        self.apply_to(arr);
+    }
+
+    /// Apply the inverse of this permutation to an array, reordering the first `ELEMENTS` elements
+    pub fn apply_inverse<T>(&self, arr: &mut [T]) {
+        self.apply_inverse_to(arr);
+    }
+
+    /// Apply this permutation to any permutable container, reordering the first `ELEMENTS` elements
+    pub fn apply_to<P: Permutable + ?Sized>(&self, container: &mut P) {
+        for (i, &swap) in self.swaps.iter().enumerate() {
+            container.swap_elements(i, usize::from(swap) + i);
+        }
+    }
+
+    /// Apply the inverse of this permutation to any permutable container, reordering the first `ELEMENTS` elements
+    pub fn apply_inverse_to<P: Permutable + ?Sized>(&self, container: &mut P) {
+        for (i, &swap) in self.swaps.iter().enumerate().rev() {
+            container.swap_elements(i, usize::from(swap) + i);
+        }
+    }
+
+    /// Apply this permutation to an array of bytes, using SIMD instructions where they are available.
+    pub fn apply_bytes(&self, bytes: &mut [u8; ELEMENTS]) {
+        self.shuffle.apply(bytes);
+    }
+
+    /// Invert this permutation
+    /// This produces the permutation that will reorder the array back to its original order
+    pub fn invert(&self) -> Self {
+        let array = self.inverse;
+        let inverse = *self.array();
+        Self {
+            shuffle: ByteShuffle::new(array),
+            inverse,
+            swaps: Permutation::<u8, ELEMENTS>::swaps_from_array(&array),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbtest::arbtest;
+
+    use super::ExpandedPermutation;
+    use crate::{inner::Inner, Permutation};
+
+    fn check<I: Inner, const ELEMENTS: usize>(perm: Permutation<I, ELEMENTS>) {
+        let expanded = ExpandedPermutation::from(perm);
+        assert_eq!(Permutation::<I, ELEMENTS>::from(expanded), perm);
+        assert_eq!(expanded.is_default(), perm.is_default());
+        assert_eq!(expanded.array(), &perm.get_array());
+        assert_eq!(expanded.swaps_array(), &perm.swaps_array());
+
+        for index in 0..(ELEMENTS as u8) {
+            assert_eq!(
+                expanded.element_at_index(index, |x| x),
+                perm.element_at_index(index, |x| x)
+            );
+            assert_eq!(
+                expanded.index_of(&index, |&x| x),
+                perm.index_of(&index, |&x| x)
+            );
+        }
+
+        let mut arr = perm.get_array();
+        expanded.apply_inverse(&mut arr);
+        assert_eq!(arr, Permutation::<I, ELEMENTS>::DEFAULT_ARRAY);
+        expanded.apply(&mut arr);
+        assert_eq!(arr, perm.get_array());
+
+        let inverse = expanded.invert();
+        assert_eq!(Permutation::<I, ELEMENTS>::from(inverse), perm.invert());
+        assert_eq!(inverse, ExpandedPermutation::from(perm.invert()));
+    }
+
+    #[test]
+    pub fn test_expanded_all() {
+        for perm in Permutation::<u8, 5>::all() {
+            check(perm);
+        }
+        assert_eq!(
+            ExpandedPermutation::<5>::default(),
+            ExpandedPermutation::from(Permutation::<u8, 5>::default())
+        );
+    }
+
+    #[test]
+    pub fn test_expanded_arbitrary() {
+        arbtest(|u| {
+            check(u.arbitrary::<Permutation<u32, 12>>()?);
+            check(u.arbitrary::<Permutation<u128, 34>>()?);
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,155 @@
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+
+use crate::{inner::Inner, permutation_set::PermutationSet, Permutation};
+
+/// The largest number of permutations for which group membership is stored as a `PermutationSet`.
+/// Above this the group's elements are kept in a `BTreeSet` instead.
+const DENSE_LIMIT: usize = 1 << 24;
+
+/// The group generated by a set of permutations - every permutation which can be made by combining them.
+/// ```
+/// use importunate::{Permutation, group::Group};
+/// let rotate = Permutation::<u8, 4>::rotate_right();
+/// let reverse = Permutation::<u8, 4>::reverse();
+///
+/// // A rotation and a reflection generate the symmetries of a square
+/// let group = Group::generate(&[rotate, reverse]);
+/// assert_eq!(group.order(), 8);
+/// assert!(group.contains(&rotate.combine(&reverse)));
+/// assert!(!group.contains(&Permutation::calculate_unchecked([1, 0, 2, 3], |&x| x)));
+/// ```
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct Group<I: Inner, const ELEMENTS: usize> {
+    /// The elements in the order they were found
+    elements: Vec<Permutation<I, ELEMENTS>>,
+    members: Members<I, ELEMENTS>,
+}
+
+#[derive(Clone, Debug)]
+enum Members<I: Inner, const ELEMENTS: usize> {
+    Dense(PermutationSet<I, ELEMENTS>),
+    Sparse(BTreeSet<Permutation<I, ELEMENTS>>),
+}
+
+impl<I: Inner, const ELEMENTS: usize> Members<I, ELEMENTS> {
+    fn new() -> Self {
+        let dense = I::get_factorial(ELEMENTS)
+            .try_into()
+            .is_ok_and(|total: usize| total <= DENSE_LIMIT);
+        if dense {
+            Self::Dense(PermutationSet::new())
+        } else {
+            Self::Sparse(BTreeSet::new())
+        }
+    }
+
+    fn insert(&mut self, perm: Permutation<I, ELEMENTS>) -> bool {
+        match self {
+            Self::Dense(set) => set.insert(perm),
+            Self::Sparse(set) => set.insert(perm),
+        }
+    }
+
+    fn contains(&self, perm: &Permutation<I, ELEMENTS>) -> bool {
+        match self {
+            Self::Dense(set) => set.contains(perm),
+            Self::Sparse(set) => set.contains(perm),
+        }
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize> Group<I, ELEMENTS> {
+    /// Generate the group from these permutations.
+    /// The group always contains the default permutation, even if there are no generators.
+    ///
+    /// This finds every element of the group so it is only suitable for groups of modest order
+    pub fn generate(generators: &[Permutation<I, ELEMENTS>]) -> Self {
+        let identity = Permutation::<I, ELEMENTS>::default();
+        let mut members = Members::new();
+        members.insert(identity);
+        let mut elements = vec![identity];
+
+        let mut index = 0;
+        while let Some(&element) = elements.get(index) {
+            for generator in generators {
+                let next = element.combine(generator);
+                if members.insert(next) {
+                    elements.push(next);
+                }
+            }
+            index += 1;
+        }
+
+        Self { elements, members }
+    }
+
+    /// The number of elements in the group
+    #[must_use]
+    pub fn order(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Is this permutation an element of the group
+    #[must_use]
+    pub fn contains(&self, perm: &Permutation<I, ELEMENTS>) -> bool {
+        self.members.contains(perm)
+    }
+
+    /// Iterate through the elements of the group, in order of the fewest generators needed to make them
+    #[must_use]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Permutation<I, ELEMENTS>> + '_ {
+        self.elements.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::Group;
+    use crate::Permutation;
+
+    #[test]
+    pub fn test_cyclic() {
+        for perm in Permutation::<u8, 5>::all() {
+            let group = Group::generate(&[perm]);
+            let cycle = perm.generate_cycle().collect_vec();
+            assert_eq!(group.order(), cycle.len());
+            for element in cycle {
+                assert!(group.contains(&element));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_symmetric() {
+        let swap = Permutation::<u16, 6>::calculate_unchecked([1, 0, 2, 3, 4, 5], |&x| x);
+        let group = Group::generate(&[swap, Permutation::rotate_right()]);
+        assert_eq!(group.order(), 720);
+        assert!(Permutation::<u16, 6>::all().all(|perm| group.contains(&perm)));
+        assert!(group.iter().all_unique());
+    }
+
+    #[test]
+    pub fn test_alternating() {
+        let three_cycles = [[1, 2, 0, 3, 4], [0, 2, 3, 1, 4], [0, 1, 3, 4, 2]]
+            .map(|arr| Permutation::<u8, 5>::calculate_unchecked(arr, |&x| x));
+        let group = Group::generate(&three_cycles);
+        assert_eq!(group.order(), 60);
+        for perm in Permutation::<u8, 5>::all() {
+            let even = perm.swaps().filter(|&swap| swap != 0).count() % 2 == 0;
+            assert_eq!(group.contains(&perm), even);
+        }
+    }
+
+    #[test]
+    pub fn test_sparse() {
+        let rotate = Permutation::<u64, 20>::rotate_right();
+        let reverse = Permutation::<u64, 20>::reverse();
+        let group = Group::generate(&[rotate, reverse]);
+        assert_eq!(group.order(), 40);
+        assert!(group.contains(&rotate.combine(&reverse)));
+        assert!(!group.contains(&Permutation::<u64, 20>::interleave(2)));
+    }
+}
@@ -0,0 +1,350 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{inner::Inner, stabilizer_chain::StabilizerChain, Permutation};
+
+/// Can be used to solve permutations with too many elements for a `SolveContext`,
+/// finding the shortest combination of a fixed set of moves that leads to it.
+///
+/// This uses iterative deepening A* search. The lower bounds on the number of moves needed come from pattern databases,
+/// each of which stores the exact number of moves needed to put a few tracked elements in place, ignoring the others.
+/// A pattern database has an entry for every arrangement of its tracked elements,
+/// so tracking `k` of `n` elements takes `n! / (n - k)!` bytes.
+///
+/// More and larger patterns make the search faster. The solutions are always optimal but may take a long time to find
+/// ```
+/// use importunate::{Permutation, ida_solver::IdaSolveContext};
+/// // Swap the first element with each of the others
+/// let swap = |i: usize| {
+///     let mut arr = core::array::from_fn(|j| j as u8);
+///     arr.swap(0, i);
+///     Permutation::<u64, 16>::calculate_unchecked(arr, |&x| x)
+/// };
+/// let moves: Vec<_> = (1..16).map(swap).collect();
+/// let context = IdaSolveContext::new(moves.clone(), &[&[1, 2, 3, 4], &[5, 6, 7, 8], &[9, 10, 11, 12], &[13, 14, 15]]);
+///
+/// let perm = moves[3].combine(&moves[8]).combine(&moves[11]);
+/// let solution = context.solve(perm).unwrap();
+/// assert_eq!(solution, [moves[11], moves[8], moves[3]]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdaSolveContext<I: Inner, const ELEMENTS: usize> {
+    moves: Vec<Permutation<I, ELEMENTS>>,
+    /// The inverse array of each move, which maps each position to the position its element moves to
+    inverse_arrays: Vec<[u8; ELEMENTS]>,
+    /// The index of the inverse of each move
+    inverse_indices: Vec<usize>,
+    patterns: Vec<PatternDatabase<ELEMENTS>>,
+    /// Used to check whether a permutation can be solved before searching
+    chain: StabilizerChain<I, ELEMENTS>,
+}
+
+/// The number of moves needed to put some tracked elements in place, for every arrangement of those elements
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PatternDatabase<const ELEMENTS: usize> {
+    tracked: Vec<u8>,
+    /// The number of moves needed for each arrangement, or `u8::MAX` if it cannot be solved
+    distances: Vec<u8>,
+}
+
+/// The index of an arrangement of tracked elements, given the position of each one
+fn arrangement_index<const ELEMENTS: usize>(positions: &[u8]) -> usize {
+    let mut index = 0;
+    for (i, &position) in positions.iter().enumerate() {
+        let smaller_before = positions[..i].iter().filter(|&&p| p < position).count();
+        index = index * (ELEMENTS - i) + usize::from(position) - smaller_before;
+    }
+    index
+}
+
+/// The position of each tracked element in the arrangement with this index. The inverse of `arrangement_index`
+fn arrangement_positions<const ELEMENTS: usize>(
+    mut index: usize,
+    tracked: usize,
+) -> [u8; ELEMENTS] {
+    let mut digits = [0usize; ELEMENTS];
+    for i in (0..tracked).rev() {
+        digits[i] = index % (ELEMENTS - i);
+        index /= ELEMENTS - i;
+    }
+
+    // Each digit is the position among those not used by an earlier element
+    let mut used = [false; ELEMENTS];
+    let mut positions = [0u8; ELEMENTS];
+    for (position, &digit) in positions.iter_mut().zip(&digits[..tracked]) {
+        let p = (0..ELEMENTS)
+            .filter(|&p| !used[p])
+            .nth(digit)
+            .unwrap_or_else(|| unreachable!());
+        used[p] = true;
+        *position = p as u8;
+    }
+    positions
+}
+
+impl<const ELEMENTS: usize> PatternDatabase<ELEMENTS> {
+    /// Search backwards from solved, moving the tracked elements with the inverse arrays of the moves.
+    /// Each arrangement is set when it is first reached, so the frontiers only hold the index of each arrangement once
+    fn new(inverse_arrays: &[[u8; ELEMENTS]], tracked: &[u8]) -> Self {
+        assert!(
+            tracked.iter().all(|&x| usize::from(x) < ELEMENTS),
+            "Tracked elements must be less than {ELEMENTS}"
+        );
+        assert!(
+            tracked
+                .iter()
+                .enumerate()
+                .all(|(i, x)| !tracked[..i].contains(x)),
+            "Tracked elements must be unique"
+        );
+        let len = (0..tracked.len())
+            .try_fold(1usize, |len, i| len.checked_mul(ELEMENTS - i))
+            .unwrap_or_else(|| panic!("Cannot track {} elements", tracked.len()));
+
+        let mut distances = vec![u8::MAX; len];
+        let solved = arrangement_index::<ELEMENTS>(tracked);
+        distances[solved] = 0;
+        let mut distance = 0u8;
+        let mut current = vec![solved];
+        let mut next = vec![];
+        while !current.is_empty() {
+            for index in current.drain(..) {
+                let positions = arrangement_positions::<ELEMENTS>(index, tracked.len());
+                for inverse in inverse_arrays {
+                    let moved = positions.map(|p| inverse[usize::from(p)]);
+                    let moved_index = arrangement_index::<ELEMENTS>(&moved[..tracked.len()]);
+                    if distances[moved_index] == u8::MAX {
+                        assert!(
+                            distance + 1 < u8::MAX,
+                            "Patterns need too many moves to solve"
+                        );
+                        distances[moved_index] = distance + 1;
+                        next.push(moved_index);
+                    }
+                }
+            }
+            core::mem::swap(&mut current, &mut next);
+            distance += 1;
+        }
+
+        Self {
+            tracked: tracked.to_vec(),
+            distances,
+        }
+    }
+
+    /// The number of moves needed to put the tracked elements in place
+    fn distance(&self, positions: &[u8; ELEMENTS]) -> u8 {
+        let mut tracked = [0u8; ELEMENTS];
+        for (t, &x) in tracked.iter_mut().zip(&self.tracked) {
+            *t = positions[usize::from(x)];
+        }
+        self.distances[arrangement_index::<ELEMENTS>(&tracked[..self.tracked.len()])]
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize> IdaSolveContext<I, ELEMENTS> {
+    /// Create a new solver from a fixed set of moves and the elements tracked by each pattern database.
+    /// This will also use the inverses of those moves
+    /// # Panics
+    ///
+    /// This will panic if any tracked element is not less than `ELEMENTS` or is tracked twice by the same pattern,
+    /// if any pattern database would have more than `usize::MAX` entries,
+    /// or if any pattern needs `u8::MAX` or more moves to solve
+    #[must_use]
+    pub fn new(mut moves: Vec<Permutation<I, ELEMENTS>>, patterns: &[&[u8]]) -> Self {
+        for m in &moves.clone() {
+            moves.push(m.invert());
+        }
+        moves.sort();
+        moves.dedup();
+
+        let inverse_arrays: Vec<_> = moves.iter().map(|m| m.invert().get_array()).collect();
+        let inverse_indices = moves
+            .iter()
+            .map(|m| {
+                moves
+                    .binary_search(&m.invert())
+                    .unwrap_or_else(|_| unreachable!())
+            })
+            .collect();
+        let patterns = patterns
+            .iter()
+            .map(|tracked| PatternDatabase::new(&inverse_arrays, tracked))
+            .collect();
+        let chain = StabilizerChain::new(&moves);
+
+        Self {
+            moves,
+            inverse_arrays,
+            inverse_indices,
+            patterns,
+            chain,
+        }
+    }
+
+    /// The moves this solver uses, including their inverses
+    pub fn moves(&self) -> &[Permutation<I, ELEMENTS>] {
+        &self.moves
+    }
+
+    /// A lower bound on the number of moves needed to solve this permutation, from the pattern databases
+    #[must_use]
+    pub fn lower_bound(&self, perm: Permutation<I, ELEMENTS>) -> usize {
+        self.bound_of_positions(&perm.invert().get_array())
+    }
+
+    fn bound_of_positions(&self, positions: &[u8; ELEMENTS]) -> usize {
+        self.patterns
+            .iter()
+            .map(|pattern| usize::from(pattern.distance(positions)))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Try to Deconstruct the inverse of this permutation into the shortest sequence of moves.
+    /// Returns `None` if the moves cannot solve it
+    #[must_use]
+    pub fn solve(&self, perm: Permutation<I, ELEMENTS>) -> Option<Vec<Permutation<I, ELEMENTS>>> {
+        if !self.chain.contains(&perm) {
+            return None;
+        }
+
+        // Search with the position of each element, which is what the pattern databases need
+        let positions = perm.invert().get_array();
+        let mut bound = self.bound_of_positions(&positions);
+        let mut path = vec![];
+        loop {
+            let mut next_bound = usize::MAX;
+            if self.search(&positions, bound, &mut path, &mut next_bound) {
+                return Some(path.into_iter().map(|index| self.moves[index]).collect());
+            }
+            bound = next_bound;
+        }
+    }
+
+    /// Search for a solution with at most `bound` moves in total, adding to `path`.
+    /// Otherwise lowers `next_bound` to the smallest total beyond `bound` which might lead to a solution
+    fn search(
+        &self,
+        positions: &[u8; ELEMENTS],
+        bound: usize,
+        path: &mut Vec<usize>,
+        next_bound: &mut usize,
+    ) -> bool {
+        if *positions == Permutation::<I, ELEMENTS>::DEFAULT_ARRAY {
+            return true;
+        }
+        let estimate = path.len() + self.bound_of_positions(positions).max(1);
+        if estimate > bound {
+            *next_bound = (*next_bound).min(estimate);
+            return false;
+        }
+
+        for (index, inverse) in self.inverse_arrays.iter().enumerate() {
+            // Undoing the previous move never helps
+            if path
+                .last()
+                .is_some_and(|&last| self.inverse_indices[last] == index)
+            {
+                continue;
+            }
+
+            path.push(index);
+            let moved = positions.map(|p| inverse[usize::from(p)]);
+            if self.search(&moved, bound, path, next_bound) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::{arrangement_index, arrangement_positions, IdaSolveContext};
+    use crate::{solver::SolveContext, Permutation};
+
+    #[test]
+    pub fn test_optimal() {
+        let moves = vec![
+            Permutation::<u16, 7>::rotate_right(),
+            Permutation::reverse(),
+            Permutation::from_swaps([1].into_iter()),
+        ];
+        let context = SolveContext::new(moves.clone());
+        let ida = IdaSolveContext::new(moves, &[&[0, 1, 2], &[3, 4, 5, 6]]);
+        assert_eq!(ida.moves(), context.moves());
+
+        for perm in Permutation::<u16, 7>::all().step_by(13) {
+            let distance = context.distance(perm);
+            let solution = ida.solve(perm);
+            assert_eq!(solution.as_ref().map(Vec::len), distance);
+            if let Some(solution) = solution {
+                assert!(ida.lower_bound(perm) <= solution.len());
+                assert!(context.is_path(perm, &solution, Permutation::default()));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_unsolvable() {
+        let moves = vec![Permutation::<u16, 7>::rotate_right()];
+        let ida = IdaSolveContext::new(moves, &[&[0, 1]]);
+        assert_eq!(ida.solve(Permutation::reverse()), None);
+        assert_eq!(
+            ida.solve(Permutation::rotate_left()),
+            Some(vec![Permutation::rotate_right()])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Tracked elements must be unique")]
+    pub fn test_repeated_tracked_element() {
+        let _ = IdaSolveContext::new(vec![Permutation::<u16, 7>::rotate_right()], &[&[1, 1]]);
+    }
+
+    #[test]
+    pub fn test_arrangement_positions() {
+        for positions in (0..6u8).permutations(3) {
+            let index = arrangement_index::<6>(&positions);
+            assert!(index < 6 * 5 * 4);
+            assert_eq!(arrangement_positions::<6>(index, 3)[..3], positions);
+        }
+    }
+
+    #[test]
+    pub fn test_large() {
+        let moves = (1..20u8)
+            .map(|i| Permutation::<u128, 20>::from_swaps([i].into_iter()))
+            .collect_vec();
+        let ida = IdaSolveContext::new(moves.clone(), &[&[1, 2, 3, 4], &[5, 6, 7, 8]]);
+        let perm = [2, 7, 4, 1, 7, 3]
+            .iter()
+            .fold(Permutation::default(), |p, &i| p.combine(&moves[i]));
+        // Sorting with swaps of the first element takes one swap per element of the cycle containing it, not counting itself,
+        // and two more than the length of each other cycle
+        let arr = perm.get_array();
+        let mut seen = [false; 20];
+        let mut optimal = 0;
+        for start in 0..20 {
+            let mut len = 0;
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                i = usize::from(arr[i]);
+                len += 1;
+            }
+            if len > 1 {
+                optimal += if start == 0 { len - 1 } else { len + 1 };
+            }
+        }
+        assert_eq!(optimal, 6);
+
+        let solution = ida.solve(perm).unwrap();
+        assert_eq!(solution.len(), optimal);
+        assert!(solution.iter().fold(perm, |p, m| p.combine(m)).is_default());
+    }
+}
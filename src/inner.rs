@@ -60,7 +60,7 @@ macro_rules! impl_permutation_inner {
             fn to_le_byte_array<const BYTES: usize>(&self) -> [u8; BYTES] {
                 let bytes = self.to_le_bytes();
                 let mut arr = [0u8; BYTES];
-                for i in 0..(BYTES.min(arr.len())) {
+                for i in 0..(BYTES.min(bytes.len())) {
                     arr[i] = bytes[i]
                 }
                 arr
@@ -0,0 +1,223 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{inner::Inner, solver::SolveContext, Permutation};
+
+/// A `SolveContext` whose moves have labels, so solutions can be printed and parsed like `R U R'`.
+///
+/// The inverse of each move is labelled by adding a `'` to its label, or removing one if it already ends with `'`.
+/// Moves which are their own inverse, and moves whose inverse has a label of its own, keep their labels.
+/// ```
+/// use importunate::{Permutation, labelled_solver::LabelledSolveContext};
+/// let rotate = Permutation::<u8, 4>::rotate_right();
+/// let reverse = Permutation::<u8, 4>::reverse();
+/// let context = LabelledSolveContext::new([("R", rotate), ("F", reverse)]);
+///
+/// let perm = rotate.combine(&reverse);
+/// let solution = context.solve(perm).unwrap();
+/// assert_eq!(solution.join(" "), "F R'");
+///
+/// let moves = context.parse("F R'").unwrap();
+/// assert_eq!(moves.into_iter().fold(perm, |p, m| p.combine(&m)), Permutation::default());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LabelledSolveContext<I: Inner, const ELEMENTS: usize, T: AsRef<[u8]> = Vec<u8>> {
+    context: SolveContext<I, ELEMENTS, T>,
+    /// The label of each of the context's moves, in the same order
+    labels: Vec<String>,
+}
+
+/// The label for the inverse of a move
+fn inverse_label(label: &str) -> String {
+    match label.strip_suffix('\'') {
+        Some(stripped) => stripped.to_string(),
+        None => format!("{label}'"),
+    }
+}
+
+/// Label every move and its inverse, in order of permutation
+fn label_moves<I: Inner, const ELEMENTS: usize, S: Into<String>>(
+    moves: impl IntoIterator<Item = (S, Permutation<I, ELEMENTS>)>,
+) -> Vec<(Permutation<I, ELEMENTS>, String)> {
+    let mut labelled: Vec<_> = moves
+        .into_iter()
+        .map(|(label, m)| (m, label.into()))
+        .collect();
+    for (m, label) in labelled.clone() {
+        let inverse = m.invert();
+        if labelled.iter().all(|(x, _)| *x != inverse) {
+            labelled.push((inverse, inverse_label(&label)));
+        }
+    }
+    labelled.sort_by_key(|(m, _)| *m);
+    labelled.dedup_by_key(|(m, _)| *m);
+
+    for (index, (_, label)) in labelled.iter().enumerate() {
+        assert!(
+            !label.is_empty() && !label.contains(char::is_whitespace),
+            "Move labels must not be empty or contain whitespace: {label:?}"
+        );
+        assert!(
+            labelled[..index].iter().all(|(_, other)| other != label),
+            "Move label {label:?} is used for more than one move"
+        );
+    }
+    labelled
+}
+
+impl<I: Inner, const ELEMENTS: usize> LabelledSolveContext<I, ELEMENTS> {
+    /// Create a new solver from a fixed set of labelled moves. This will also use the inverses of those moves.
+    /// If the same move is given more than once, the first label is used
+    /// # Panics
+    ///
+    /// This will panic if the number of possible solutions is greater than `usize::MAX`,
+    /// or if any label is empty, contains whitespace or is used for more than one move
+    #[must_use]
+    pub fn new<S: Into<String>>(
+        moves: impl IntoIterator<Item = (S, Permutation<I, ELEMENTS>)>,
+    ) -> Self {
+        let labelled = label_moves(moves);
+        let context = SolveContext::new(labelled.iter().map(|(m, _)| *m).collect());
+        Self {
+            context,
+            labels: labelled.into_iter().map(|(_, label)| label).collect(),
+        }
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize, T: AsRef<[u8]>> LabelledSolveContext<I, ELEMENTS, T> {
+    /// Label the moves of an existing solver, such as one read with `SolveContext::from_bytes`.
+    /// Returns `None` if the labelled moves and their inverses are not the solver's moves
+    /// # Panics
+    ///
+    /// This will panic if any label is empty, contains whitespace or is used for more than one move
+    pub fn from_context<S: Into<String>>(
+        context: SolveContext<I, ELEMENTS, T>,
+        moves: impl IntoIterator<Item = (S, Permutation<I, ELEMENTS>)>,
+    ) -> Option<Self> {
+        let labelled = label_moves(moves);
+        if !labelled.iter().map(|(m, _)| m).eq(context.moves()) {
+            return None;
+        }
+        Some(Self {
+            context,
+            labels: labelled.into_iter().map(|(_, label)| label).collect(),
+        })
+    }
+
+    /// The underlying solver
+    pub fn context(&self) -> &SolveContext<I, ELEMENTS, T> {
+        &self.context
+    }
+
+    /// The label of this move, or `None` if it is not one of the solver's moves
+    #[must_use]
+    pub fn label(&self, m: &Permutation<I, ELEMENTS>) -> Option<&str> {
+        let index = self.context.moves().binary_search(m).ok()?;
+        Some(&self.labels[index])
+    }
+
+    /// The move with this label, or `None` if there is no such move
+    #[must_use]
+    pub fn move_labelled(&self, label: &str) -> Option<Permutation<I, ELEMENTS>> {
+        let index = self.labels.iter().position(|l| l == label)?;
+        Some(self.context.moves()[index])
+    }
+
+    /// Try to Deconstruct the inverse of this permutation into the shortest sequence of moves, returning their labels
+    #[must_use]
+    pub fn solve(&self, perm: Permutation<I, ELEMENTS>) -> Option<Vec<&str>> {
+        let solution = self.context.solve(perm)?;
+        Some(
+            solution
+                .iter()
+                .map(|m| self.label(m).unwrap_or_else(|| unreachable!()))
+                .collect(),
+        )
+    }
+
+    /// Parse a sequence of labels separated by whitespace into moves.
+    /// Returns `None` if any label is not the label of a move
+    #[must_use]
+    pub fn parse(&self, text: &str) -> Option<Vec<Permutation<I, ELEMENTS>>> {
+        text.split_whitespace()
+            .map(|label| self.move_labelled(label))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::LabelledSolveContext;
+    use crate::{solver::SolveContext, Permutation};
+
+    type Perm = Permutation<u8, 5>;
+
+    #[test]
+    pub fn test_labels() {
+        let context = LabelledSolveContext::new([
+            ("R", Perm::rotate_right()),
+            ("L'", Perm::rotate_right().combine(&Perm::rotate_right())),
+            ("F", Perm::reverse()),
+        ]);
+        let labels = context
+            .context()
+            .moves()
+            .iter()
+            .map(|m| context.label(m).unwrap())
+            .sorted()
+            .collect_vec();
+        assert_eq!(labels, ["F", "L", "L'", "R", "R'"]);
+        assert_eq!(context.move_labelled("R'"), Some(Perm::rotate_left()));
+        assert_eq!(
+            context.move_labelled("L"),
+            Perm::rotate_left().combine(&Perm::rotate_left()).into()
+        );
+        assert_eq!(context.move_labelled("F'"), None);
+        assert_eq!(context.parse("R F X"), None);
+
+        for perm in Perm::all() {
+            let Some(solution) = context.solve(perm) else {
+                continue;
+            };
+            assert_eq!(solution.len(), context.context().distance(perm).unwrap());
+            let text = solution.join(" ");
+            let moves = context.parse(&text).unwrap();
+            let solved = moves.iter().fold(perm, |p, m| p.combine(m));
+            assert!(solved.is_default());
+        }
+    }
+
+    #[test]
+    pub fn test_from_context() {
+        let moves = vec![Perm::rotate_right(), Perm::reverse()];
+        let mut bytes = vec![];
+        SolveContext::new(moves.clone())
+            .write_to(&mut bytes)
+            .unwrap();
+        let saved = SolveContext::from_bytes(&bytes, moves).unwrap();
+
+        let context = LabelledSolveContext::from_context(
+            saved.clone(),
+            [("R", Perm::rotate_right()), ("F", Perm::reverse())],
+        )
+        .unwrap();
+        assert_eq!(context.solve(Perm::rotate_left()), Some(vec!["R"]));
+
+        assert_eq!(
+            LabelledSolveContext::from_context(saved, [("R", Perm::rotate_right())]),
+            None
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "used for more than one move")]
+    pub fn test_duplicate_labels() {
+        let _ = LabelledSolveContext::new([("R", Perm::rotate_right()), ("R", Perm::reverse())]);
+    }
+}
@@ -41,18 +41,56 @@
 
 // TODO
 // documentation
-// optional rand
 // errors
 
+/// Fast reordering of byte arrays
+pub mod byte_shuffle;
 mod cyclic_generator;
 mod decomposer;
+/// Permutations with their arrays precalculated, for fast repeated use
+pub mod expanded;
 /// Inner types that Permutations can use
 pub mod inner;
+mod min_swaps;
+/// Containers which can be reordered by permutations
+pub mod permutable;
+/// Views and iterators which read elements in permuted order
+pub mod permuted_view;
 mod swaps_iterator;
 
-#[cfg(any(test, feature = "std"))]
+#[cfg(any(test, feature = "alloc"))]
+extern crate alloc;
+
+#[cfg(any(test, feature = "alloc"))]
+/// Precalculated multiplication tables for permutations of small numbers of elements
+pub mod cayley_table;
+#[cfg(any(test, feature = "alloc"))]
+/// Solving permutations with moves which cannot be undone
+pub mod directed_solver;
+#[cfg(any(test, feature = "alloc"))]
+/// Groups generated by sets of permutations
+pub mod group;
+#[cfg(any(test, feature = "alloc"))]
+/// Solving permutations with too many elements for a table, using iterative deepening A* search
+pub mod ida_solver;
+#[cfg(any(test, feature = "alloc"))]
+/// Solving permutations with labelled moves
+pub mod labelled_solver;
+#[cfg(any(test, feature = "alloc"))]
+/// Dense maps from permutations to values
+pub mod permutation_map;
+#[cfg(any(test, feature = "alloc"))]
+/// Dense sets of permutations
+pub mod permutation_set;
+#[cfg(any(test, feature = "alloc"))]
 /// Allows you to solve permutations - finding the shortest sequence of permutations that lead to it
 pub mod solver;
+#[cfg(any(test, feature = "alloc"))]
+/// Membership testing and counting for groups too large to search
+pub mod stabilizer_chain;
+#[cfg(any(test, feature = "alloc"))]
+/// Allows you to solve permutations with moves of different costs
+pub mod weighted_solver;
 
 use core::fmt::Display;
 use core::hash::Hash;
@@ -145,17 +183,137 @@ impl<I: Inner, const ELEMENTS: usize> Permutation<I, ELEMENTS> {
 
     /// Apply this permutation to an array, reordering the first `ELEMENTS` elements
     pub fn apply<T>(&self, arr: &mut [T]) {
-        for (i, swap) in self.swaps().enumerate() {
-            arr.swap(i, usize::from(swap) + i);
-        }
+        self.apply_to(arr);
     }
     /// Apply the inverse of this permutation to an array, reordering the first `ELEMENTS` elements
     pub fn apply_inverse<T>(&self, arr: &mut [T]) {
+        self.apply_inverse_to(arr);
+    }
+
+    /// Apply this permutation to any permutable container, reordering the first `ELEMENTS` elements
+    pub fn apply_to<P: permutable::Permutable + ?Sized>(&self, container: &mut P) {
+        for (i, swap) in self.swaps().enumerate() {
+            container.swap_elements(i, usize::from(swap) + i);
+        }
+    }
+    /// Apply the inverse of this permutation to any permutable container, reordering the first `ELEMENTS` elements
+    pub fn apply_inverse_to<P: permutable::Permutable + ?Sized>(&self, container: &mut P) {
         for (i, swap) in self.swaps_array().into_iter().enumerate().rev() {
-            arr.swap(i, usize::from(swap) + i);
+            container.swap_elements(i, usize::from(swap) + i);
+        }
+    }
+
+    /// Apply this permutation to several containers at once, reordering the first `ELEMENTS` elements of each.
+    /// The swaps are only calculated once.
+    /// To reorder containers of different types together, use a tuple of them with `apply_to`
+    /// ```
+    /// use importunate::Permutation;
+    /// let perm = Permutation::<u8, 3>::calculate_unchecked([2, 0, 1], |&x| x);
+    /// let mut xs = [10, 20, 30];
+    /// let mut ys = [11, 21, 31];
+    /// perm.apply_all(&mut [&mut xs, &mut ys]);
+    /// assert_eq!(xs, [30, 10, 20]);
+    /// assert_eq!(ys, [31, 11, 21]);
+    ///
+    /// let mut names = ["a", "b", "c"];
+    /// perm.apply_to(&mut (&mut xs, &mut names));
+    /// assert_eq!(xs, [20, 30, 10]);
+    /// assert_eq!(names, ["c", "a", "b"]);
+    /// ```
+    pub fn apply_all<P: permutable::Permutable>(&self, containers: &mut [P]) {
+        let swaps = self.swaps_array();
+        for container in containers {
+            Self::apply_swaps(&swaps, container);
+        }
+    }
+    /// Apply the inverse of this permutation to several containers at once, reordering the first `ELEMENTS` elements of each.
+    /// The swaps are only calculated once.
+    pub fn apply_inverse_all<P: permutable::Permutable>(&self, containers: &mut [P]) {
+        let swaps = self.swaps_array();
+        for container in containers {
+            for (i, &swap) in swaps.iter().enumerate().rev() {
+                container.swap_elements(i, usize::from(swap) + i);
+            }
         }
     }
 
+    fn apply_swaps<P: permutable::Permutable + ?Sized>(swaps: &[u8; ELEMENTS], container: &mut P) {
+        for (i, &swap) in swaps.iter().enumerate() {
+            container.swap_elements(i, usize::from(swap) + i);
+        }
+    }
+
+    /// Apply this permutation to every consecutive chunk of `ELEMENTS` elements in the slice.
+    /// The swaps are only calculated once.
+    /// # Panics
+    ///
+    /// This will panic if the length of the slice is not a multiple of `ELEMENTS`
+    /// ```
+    /// use importunate::Permutation;
+    /// let perm = Permutation::<u8, 3>::calculate_unchecked([2, 1, 0], |&x| x);
+    /// let mut pixels = [1, 2, 3, 4, 5, 6];
+    /// perm.apply_chunks(&mut pixels);
+    /// assert_eq!(pixels, [3, 2, 1, 6, 5, 4]);
+    /// ```
+    // `is_multiple_of` needs Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn apply_chunks<T>(&self, slice: &mut [T]) {
+        assert!(
+            slice.len() % ELEMENTS == 0,
+            "slice length {} is not a multiple of {ELEMENTS}",
+            slice.len()
+        );
+        self.apply_chunks_exact(slice);
+    }
+
+    /// Apply this permutation to every consecutive chunk of `ELEMENTS` elements in the slice.
+    /// The swaps are only calculated once.
+    /// Any elements at the end of the slice which do not make up a whole chunk are left in place and returned.
+    pub fn apply_chunks_exact<'a, T>(&self, slice: &'a mut [T]) -> &'a mut [T] {
+        let swaps = self.swaps_array();
+        let mut chunks = slice.chunks_exact_mut(ELEMENTS);
+        for chunk in &mut chunks {
+            Self::apply_swaps(&swaps, chunk);
+        }
+        chunks.into_remainder()
+    }
+
+    /// Apply this permutation to every consecutive chunk of `ELEMENTS` elements in the slice, in parallel.
+    /// # Panics
+    ///
+    /// This will panic if the length of the slice is not a multiple of `ELEMENTS`
+    #[cfg(any(test, feature = "rayon"))]
+    // `is_multiple_of` needs Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn par_apply_chunks<T: Send>(&self, slice: &mut [T]) {
+        assert!(
+            slice.len() % ELEMENTS == 0,
+            "slice length {} is not a multiple of {ELEMENTS}",
+            slice.len()
+        );
+        self.par_apply_chunks_exact(slice);
+    }
+
+    /// Apply this permutation to every consecutive chunk of `ELEMENTS` elements in the slice, in parallel.
+    /// Any elements at the end of the slice which do not make up a whole chunk are left in place and returned.
+    #[cfg(any(test, feature = "rayon"))]
+    pub fn par_apply_chunks_exact<'a, T: Send>(&self, slice: &'a mut [T]) -> &'a mut [T] {
+        use rayon::prelude::*;
+        let swaps = self.swaps_array();
+        let mut chunks = slice.par_chunks_exact_mut(ELEMENTS);
+        let remainder = chunks.take_remainder();
+        chunks.for_each(|chunk| Self::apply_swaps(&swaps, chunk));
+        remainder
+    }
+
+    /// A read only view of the slice, with the first `ELEMENTS` elements reordered by this permutation
+    /// # Panics
+    ///
+    /// This will panic if the slice has fewer than `ELEMENTS` elements
+    pub fn view<'a, T>(&self, slice: &'a [T]) -> permuted_view::PermutedView<'a, T, I, ELEMENTS> {
+        permuted_view::PermutedView::new(slice, *self)
+    }
+
     /// The range of all possible permutations of this number of elements
     #[must_use]
     pub fn all() -> impl DoubleEndedIterator<Item = Self> {
@@ -211,6 +369,27 @@ impl<I: Inner, const ELEMENTS: usize> Permutation<I, ELEMENTS> {
         Self::from_swaps(other.swaps())
     }
 
+    /// The number of permutations of this number of elements, for sizing tables indexed by permutation
+    #[cfg(any(test, feature = "alloc"))]
+    fn table_len() -> usize {
+        let Ok(total) = I::get_factorial(ELEMENTS).try_into() else {
+            panic!("Cannot make a table for {ELEMENTS} elements!");
+        };
+        total
+    }
+
+    /// The index of this permutation in a table of every permutation
+    #[cfg(any(test, feature = "alloc"))]
+    fn table_index(&self) -> usize {
+        self.0.try_into().unwrap_or_else(|_| unreachable!())
+    }
+
+    /// The permutation at this index in a table of every permutation
+    #[cfg(any(test, feature = "alloc"))]
+    fn from_table_index(index: usize) -> Self {
+        Self(I::try_from(index).unwrap_or_else(|_| unreachable!()))
+    }
+
     fn from_swaps(swaps: impl Iterator<Item = u8>) -> Self {
         let mut inner: I = I::zero();
         let mut mult: I = I::one();
@@ -224,6 +403,22 @@ impl<I: Inner, const ELEMENTS: usize> Permutation<I, ELEMENTS> {
         Self(inner)
     }
 
+    /// Calculate the swaps which produce an array, keeping track of where each element is
+    fn swaps_from_array(arr: &[u8; ELEMENTS]) -> [u8; ELEMENTS] {
+        let mut current = Self::DEFAULT_ARRAY;
+        let mut positions = Self::DEFAULT_ARRAY;
+        let mut swaps = [0; ELEMENTS];
+
+        for (index, &element) in arr.iter().enumerate() {
+            let position = positions[usize::from(element)];
+            let displaced = current[index];
+            current[usize::from(position)] = displaced;
+            positions[usize::from(displaced)] = position;
+            swaps[index] = position - index as u8;
+        }
+        swaps
+    }
+
     fn test_unique(iterator: impl Iterator<Item = u8>) -> bool {
         let mut test = 0u64;
 
@@ -262,33 +457,23 @@ impl<I: Inner, const ELEMENTS: usize> Permutation<I, ELEMENTS> {
     /// Calculate the permutation of an array.
     /// # Panics
     ///
-    /// This will panic or loop forever if the array's elements contain duplicates or elements outsize `0..ELEMENTS`
-    pub fn calculate_unchecked<T, F: Fn(&T) -> u8>(mut arr: [T; ELEMENTS], mut f: F) -> Self {
-        debug_assert!(Self::test_unique(arr.iter().map(&mut f)));
-        let mut slot_multiplier: I = I::one();
-        let mut inner: I = I::zero();
-        for index in 0..(ELEMENTS as u8) {
-            let mut swap_element = f(&arr[usize::from(index)]);
-
-            'inner: loop {
-                match swap_element.checked_sub(index) {
-                    None => unreachable!(), //Array is invalid
-                    Some(0) => break 'inner,
-                    Some(diff) => {
-                        swap_element = f(&arr[usize::from(swap_element)]);
-                        if swap_element == index {
-                            let amount = f(&arr[usize::from(index)]) - index;
-
-                            arr.swap(index.into(), (index + diff).into());
-
-                            let change = slot_multiplier * (I::from(amount));
-                            inner = inner + change;
-                            break 'inner;
-                        }
-                    }
-                }
-            }
-            slot_multiplier = slot_multiplier * I::from((ELEMENTS as u8) - index);
+    /// This will panic or return a meaningless permutation if the array's elements contain duplicates or elements outsize `0..ELEMENTS`
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn calculate_unchecked<T, F: Fn(&T) -> u8>(arr: [T; ELEMENTS], f: F) -> Self {
+        debug_assert!(Self::test_unique(arr.iter().map(&f)));
+        let mut indices = [0; ELEMENTS];
+        for (index, element) in indices.iter_mut().zip(arr.iter()) {
+            *index = f(element);
+        }
+        Self::from_array(&indices)
+    }
+
+    /// Calculate the permutation of an array of old indices in a single pass
+    fn from_array(arr: &[u8; ELEMENTS]) -> Self {
+        let swaps = Self::swaps_from_array(arr);
+        let mut inner = I::zero();
+        for (index, &swap) in swaps.iter().enumerate().rev() {
+            inner = inner * I::from((ELEMENTS - index) as u8) + I::from(swap);
         }
         Self(inner)
     }
@@ -302,6 +487,86 @@ impl<I: Inner, const ELEMENTS: usize> Permutation<I, ELEMENTS> {
         Some(Self::calculate_unchecked(arr, f))
     }
 
+    /// Calculate the permutation which reorders `before` into `after`.
+    /// This will return `None` if `after` is not a reordering of `before` or if `before` contains duplicates.
+    /// See `between_min_swaps` for arrays that contain duplicates.
+    /// There is a performance penalty for using this - it will make n * n comparisons
+    /// ```
+    /// use importunate::Permutation;
+    /// let before = ["zero", "one", "two", "three"];
+    /// let after = ["two", "zero", "one", "three"];
+    /// let perm = Permutation::<u8, 4>::between(&before, &after).unwrap();
+    ///
+    /// let mut arr = before;
+    /// perm.apply(&mut arr);
+    /// assert_eq!(arr, after);
+    /// ```
+    pub fn between<T: Eq>(before: &[T; ELEMENTS], after: &[T; ELEMENTS]) -> Option<Self> {
+        let mut arr = Self::DEFAULT_ARRAY;
+        let mut used = 0u64;
+
+        for (index, element) in after.iter().enumerate() {
+            let mut matches = before
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| *x == element)
+                .map(|(i, _)| i);
+            let old_index = matches.next()?;
+            if matches.next().is_some() || used & (1 << old_index) != 0 {
+                return None;
+            }
+            used |= 1 << old_index;
+            arr[index] = old_index as u8;
+        }
+
+        Some(Self::calculate_unchecked(arr, |&x| x))
+    }
+
+    /// Calculate the permutation which reorders `before` into `after`, using a hash map to find elements.
+    /// This will return `None` if `after` is not a reordering of `before` or if `before` contains duplicates.
+    #[cfg(any(test, feature = "std"))]
+    pub fn between_hashed<T: Eq + Hash>(
+        before: &[T; ELEMENTS],
+        after: &[T; ELEMENTS],
+    ) -> Option<Self> {
+        let mut indices = std::collections::HashMap::with_capacity(ELEMENTS);
+        for (index, element) in before.iter().enumerate() {
+            if indices.insert(element, index as u8).is_some() {
+                return None;
+            }
+        }
+
+        let mut arr = Self::DEFAULT_ARRAY;
+        for (index, element) in after.iter().enumerate() {
+            arr[index] = indices.remove(element)?;
+        }
+
+        Some(Self::calculate_unchecked(arr, |&x| x))
+    }
+
+    /// Calculate the permutation which reorders `before` into `after`, even if the arrays contain duplicates.
+    /// Where there are several such permutations, the one which needs the fewest swaps is returned.
+    /// This will return `None` if `after` is not a reordering of `before`.
+    ///
+    /// Finding the fewest swaps is NP-hard in general, so this is an exhaustive search and its worst case is exponential.
+    /// Equal elements are interchangeable, so each step only tries one element for each distinct value,
+    /// and pairs of elements which belong in each other's places are always swapped first.
+    /// Arrays with no duplicates, or only two or three distinct values, are quick.
+    /// Random arrays of 34 elements with 8 to 24 distinct values take up to about 60ms in release builds,
+    /// but arrays built to defeat the search could take much longer.
+    /// ```
+    /// use importunate::Permutation;
+    /// let before = ['a', 'b', 'a', 'b'];
+    /// let after = ['b', 'a', 'a', 'b'];
+    /// let perm = Permutation::<u8, 4>::between_min_swaps(&before, &after).unwrap();
+    ///
+    /// assert_eq!(perm.get_array(), [1, 0, 2, 3]);
+    /// ```
+    pub fn between_min_swaps<T: Eq>(before: &[T; ELEMENTS], after: &[T; ELEMENTS]) -> Option<Self> {
+        let arr = min_swaps::MinSwapsSearch::new(before, after).run()?;
+        Some(Self::calculate_unchecked(arr, |&x| x))
+    }
+
     /// Get the element at the given index of the permutation
     pub fn element_at_index<T, F: Fn(u8) -> T>(&self, new_index: u8, f: F) -> T {
         debug_assert!((new_index as usize) < ELEMENTS);
@@ -464,7 +729,7 @@ impl<I: Inner, const ELEMENTS: usize> Permutation<I, ELEMENTS> {
         let mut arr = self.get_array();
         rhs.apply(&mut arr);
 
-        Self::calculate_unchecked(arr, |&x| x)
+        Self::from_array(&arr)
     }
 
     /// Gets the reverse permutation for this number of elements.
@@ -556,33 +821,6 @@ impl<I: Inner, const ELEMENTS: usize> Permutation<I, ELEMENTS> {
     pub fn decompose(self) -> impl Iterator<Item = Self> {
         decomposer::Decomposer::from(self)
     }
-
-    // /// The slow (but oh so elegant) version of combine
-    // pub fn combine_2(&self, rhs: &Self) -> Self {
-    //     let mut left_swaps = self.swaps_array();
-    //     for (i, diff) in rhs.swaps().enumerate() {
-    //         Self::swap_swaps(&mut left_swaps[i..], diff);
-    //     }
-
-    //     Self::from_swaps(left_swaps.into_iter())
-    // }
-
-    // fn swap_swaps(mut swaps_arr: &mut [u8], mut diff: u8) {
-    //     while diff > 0 {
-    //         let index_of_zero =
-    //             Self::index_of_element_from_swaps(swaps_arr.iter().map(|x| *x), 0);
-    //         let element_at_n = Self::element_at_index_from_swaps(swaps_arr, diff);
-
-    //         swaps_arr[0] = element_at_n;
-    //         if index_of_zero > 0 {
-    //             let min = diff.min(index_of_zero) as usize;
-    //             diff = diff.abs_diff(index_of_zero);
-    //             swaps_arr = &mut swaps_arr[min..];
-    //         } else {
-    //             break;
-    //         }
-    //     }
-    // }
 }
 
 #[cfg(test)]
@@ -704,7 +942,7 @@ mod tests {
             Ok(())
         }
 
-        arbtest(|u| test_bytes1(u));
+        arbtest(test_bytes1);
     }
 
     #[test]
@@ -720,7 +958,7 @@ mod tests {
             Ok(())
         }
 
-        arbtest(|u| test_inner1(u));
+        arbtest(test_inner1);
     }
 
     #[test]
@@ -740,7 +978,7 @@ mod tests {
             Ok(())
         }
 
-        arbtest(|u| test_swaps1(u));
+        arbtest(test_swaps1);
     }
 
     #[test]
@@ -833,10 +1071,147 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_between() {
+        let before = ["zero", "one", "two", "three", "four"];
+        for perm in Permutation::<u8, 5>::all() {
+            let mut after = before;
+            perm.apply(&mut after);
+
+            assert_eq!(Some(perm), Permutation::<u8, 5>::between(&before, &after));
+            assert_eq!(
+                Some(perm),
+                Permutation::<u8, 5>::between_hashed(&before, &after)
+            );
+            assert_eq!(
+                Some(perm),
+                Permutation::<u8, 5>::between_min_swaps(&before, &after)
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_between_not_reordering() {
+        type Perm = Permutation<u8, 4>;
+        let before = [0, 1, 2, 3];
+        let after = [0, 1, 2, 4];
+        assert_eq!(Perm::between(&before, &after), None);
+        assert_eq!(Perm::between_hashed(&before, &after), None);
+        assert_eq!(Perm::between_min_swaps(&before, &after), None);
+
+        let before = [0, 1, 2, 2];
+        let after = [2, 1, 2, 0];
+        assert_eq!(Perm::between(&before, &after), None);
+        assert_eq!(Perm::between_hashed(&before, &after), None);
+
+        let after = [2, 1, 1, 0];
+        assert_eq!(Perm::between_min_swaps(&before, &after), None);
+    }
+
+    #[test]
+    pub fn test_between_min_swaps() {
+        type Perm = Permutation<u8, 5>;
+
+        fn count_swaps(perm: Perm) -> usize {
+            perm.decompose()
+                .map(|cycle| {
+                    let arr = cycle.get_array();
+                    (0..5).filter(|&i| arr[i] != i as u8).count() - 1
+                })
+                .sum()
+        }
+
+        for before in (0..5).map(|_| 0..3u8).multi_cartesian_product() {
+            let before: [u8; 5] = before.try_into().unwrap();
+            for perm in Perm::all() {
+                let mut after = before;
+                perm.apply(&mut after);
+
+                let actual = Perm::between_min_swaps(&before, &after).unwrap();
+                let mut arr = before;
+                actual.apply(&mut arr);
+                assert_eq!(arr, after);
+
+                let expected_swaps = Perm::all()
+                    .filter(|p| {
+                        let mut arr = before;
+                        p.apply(&mut arr);
+                        arr == after
+                    })
+                    .map(count_swaps)
+                    .min()
+                    .unwrap();
+
+                assert_eq!(count_swaps(actual), expected_swaps);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_between_min_swaps_large() {
+        type Perm = Permutation<u128, 34>;
+        let before: [u8; 34] = core::array::from_fn(|i| ((i * 7) % 10) as u8);
+        let shuffle = Perm::interleave(3).combine(&Perm::rotate_right());
+        let mut after = before;
+        shuffle.apply(&mut after);
+
+        let actual = Perm::between_min_swaps(&before, &after).unwrap();
+        let mut arr = before;
+        actual.apply(&mut arr);
+        assert_eq!(arr, after);
+
+        let count_swaps = |perm: Perm| -> usize {
+            perm.decompose()
+                .map(|cycle| {
+                    let arr = cycle.get_array();
+                    (0..34).filter(|&i| arr[i] != i as u8).count() - 1
+                })
+                .sum()
+        };
+        assert!(count_swaps(actual) <= count_swaps(shuffle));
+    }
+
+    #[test]
+    pub fn test_apply_chunks() {
+        type Perm = Permutation<u8, 4>;
+        let data = (0..27u32).collect_vec();
+        for perm in Perm::all() {
+            let mut expected = data.clone();
+            for chunk in expected.chunks_exact_mut(4) {
+                perm.apply(chunk);
+            }
+
+            let mut actual = data.clone();
+            let remainder = perm.apply_chunks_exact(&mut actual);
+            assert_eq!(remainder, [24, 25, 26]);
+            assert_eq!(actual, expected);
+
+            let mut actual = data.clone();
+            let remainder = perm.par_apply_chunks_exact(&mut actual);
+            assert_eq!(remainder, [24, 25, 26]);
+            assert_eq!(actual, expected);
+
+            let mut actual = data[..24].to_vec();
+            perm.apply_chunks(&mut actual);
+            assert_eq!(actual, expected[..24]);
+
+            let mut actual = data[..24].to_vec();
+            perm.par_apply_chunks(&mut actual);
+            assert_eq!(actual, expected[..24]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not a multiple")]
+    pub fn test_apply_chunks_inexact() {
+        let mut data = [0, 1, 2, 3, 4];
+        Permutation::<u8, 4>::get_last().apply_chunks(&mut data);
+    }
+
     #[test]
     pub fn test_calculate_with_duplicate() {
         let r = Permutation::<u8, 4>::try_calculate([0, 1, 2, 2], |x| *x);
-        assert_eq!(r, None)
+        assert_eq!(r, None);
     }
 
     #[test_case(0, "0123")]
@@ -845,7 +1220,7 @@ mod tests {
     #[test_case(3, "3120")]
     #[test_case(4, "0213")]
     #[test_case(5, "1203")]
-    pub fn should_order_correctly(o: u8, expected: &str) -> Result<(), anyhow::Error> {
+    pub fn should_order_correctly(o: u8, expected: &str) {
         let permutation: Permutation<u8, 4> = Permutation(o);
 
         let mut arr = [0, 1, 2, 3];
@@ -855,8 +1230,6 @@ mod tests {
         let actual = arr.into_iter().map(|x| x.to_string()).join("");
 
         assert_eq!(expected, actual);
-
-        Ok(())
     }
 
     macro_rules! test_max {
@@ -890,14 +1263,14 @@ mod tests {
     }
 
     #[test]
-    fn test_from_lesser(){
+    fn test_from_lesser() {
+        use std::fmt::Write;
         let mut data = String::new();
-        let arr = [0,1,2,3,4,5,6,7];
-        for perm4 in Permutation::<u8, 4>::all(){
+        let arr = [0, 1, 2, 3, 4, 5, 6, 7];
+        for perm4 in Permutation::<u8, 4>::all() {
             let perm8 = Permutation::<u16, 8>::from_lesser(perm4);
-            let mut arr = arr.clone();
+            let mut arr = arr;
             perm8.apply(&mut arr);
-            use std::fmt::Write;
             writeln!(data, "{}", arr.into_iter().join(",")).unwrap();
         }
 
@@ -0,0 +1,114 @@
+/// Searches for the reordering of one array into another which has the most cycles, and therefore needs the fewest swaps.
+pub struct MinSwapsSearch<'a, T: Eq, const ELEMENTS: usize> {
+    before: &'a [T; ELEMENTS],
+    after: &'a [T; ELEMENTS],
+    arr: [u8; ELEMENTS],
+    best: Option<[u8; ELEMENTS]>,
+    best_cycles: usize,
+}
+
+impl<'a, T: Eq, const ELEMENTS: usize> MinSwapsSearch<'a, T, ELEMENTS> {
+    pub fn new(before: &'a [T; ELEMENTS], after: &'a [T; ELEMENTS]) -> Self {
+        Self {
+            before,
+            after,
+            arr: [0; ELEMENTS],
+            best: None,
+            best_cycles: 0,
+        }
+    }
+
+    /// Find the array of old indices which needs the fewest swaps, if there is one
+    pub fn run(mut self) -> Option<[u8; ELEMENTS]> {
+        let is_reordering = self.after.iter().all(|element| {
+            let count_before = self.before.iter().filter(|x| *x == element).count();
+            let count_after = self.after.iter().filter(|x| *x == element).count();
+            count_before == count_after
+        });
+        if !is_reordering {
+            return None;
+        }
+
+        //Elements which are already in place are always best left there
+        let mut assigned = 0u64;
+        let mut cycles = 0;
+        for (index, (b, a)) in self.before.iter().zip(self.after.iter()).enumerate() {
+            if a == b {
+                self.arr[index] = index as u8;
+                assigned |= 1 << index;
+                cycles += 1;
+            }
+        }
+
+        //Swapping two elements which are each where the other belongs is always part of some best solution
+        for i in 0..ELEMENTS {
+            if assigned & (1 << i) != 0 {
+                continue;
+            }
+            let pair = (i + 1..ELEMENTS).find(|&j| {
+                assigned & (1 << j) == 0
+                    && self.before[i] == self.after[j]
+                    && self.before[j] == self.after[i]
+            });
+            if let Some(j) = pair {
+                self.arr[i] = j as u8;
+                self.arr[j] = i as u8;
+                assigned |= (1 << i) | (1 << j);
+                cycles += 1;
+            }
+        }
+
+        self.search(assigned, cycles);
+        self.best
+    }
+
+    fn can_improve(&self, assigned: u64, cycles: usize) -> bool {
+        //Every remaining cycle contains at least two elements
+        let remaining = ELEMENTS - assigned.count_ones() as usize;
+        self.best.is_none() || cycles + (remaining / 2) > self.best_cycles
+    }
+
+    /// Start a new cycle at the lowest unassigned index
+    fn search(&mut self, assigned: u64, cycles: usize) {
+        if assigned.count_ones() as usize == ELEMENTS {
+            if self.best.is_none() || cycles > self.best_cycles {
+                self.best = Some(self.arr);
+                self.best_cycles = cycles;
+            }
+            return;
+        }
+        if !self.can_improve(assigned, cycles) {
+            return;
+        }
+
+        let start = (!assigned).trailing_zeros() as usize;
+        self.extend(start, start, assigned | (1 << start), cycles);
+    }
+
+    /// Choose the old index of the element at `index`, continuing the cycle which began at `start`
+    fn extend(&mut self, start: usize, index: usize, assigned: u64, cycles: usize) {
+        //Closing the cycle as soon as possible finds good solutions early
+        if self.before[start] == self.after[index] {
+            self.arr[index] = start as u8;
+            self.search(assigned, cycles + 1);
+        }
+
+        //Elements which are equal before and after are interchangeable, so only try the first of each
+        let mut tried = 0u64;
+        for old_index in 0..ELEMENTS {
+            if assigned & (1 << old_index) != 0
+                || self.before[old_index] != self.after[index]
+                || (0..old_index)
+                    .any(|t| tried & (1 << t) != 0 && self.after[t] == self.after[old_index])
+            {
+                continue;
+            }
+            tried |= 1 << old_index;
+            if !self.can_improve(assigned, cycles + 1) {
+                return;
+            }
+            self.arr[index] = old_index as u8;
+            self.extend(start, old_index, assigned | (1 << old_index), cycles);
+        }
+    }
+}
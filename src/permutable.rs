@@ -0,0 +1,186 @@
+use crate::{inner::Inner, Permutation};
+
+/// A container whose elements can be reordered by a permutation.
+/// Implement this for your own containers to use them with `Permutation::apply_to`
+/// ```
+/// use importunate::{Permutation, permutable::Permutable};
+/// let perm = Permutation::<u8, 4>::calculate_unchecked([2, 0, 1, 3], |&x| x);
+///
+/// let arr = ["zero", "one", "two", "three"].permuted(&perm);
+/// assert_eq!(arr, ["two", "zero", "one", "three"]);
+///
+/// let mut bits = 0b0100u8;
+/// perm.apply_to(&mut bits);
+/// assert_eq!(bits, 0b0001);
+/// ```
+pub trait Permutable {
+    /// Swap the elements at indices `a` and `b`
+    fn swap_elements(&mut self, a: usize, b: usize);
+
+    /// Reorder this container by the permutation and return it
+    #[must_use]
+    fn permuted<I: Inner, const ELEMENTS: usize>(
+        mut self,
+        permutation: &Permutation<I, ELEMENTS>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        permutation.apply_to(&mut self);
+        self
+    }
+}
+
+impl<T> Permutable for [T] {
+    fn swap_elements(&mut self, a: usize, b: usize) {
+        self.swap(a, b);
+    }
+}
+
+impl<T, const N: usize> Permutable for [T; N] {
+    fn swap_elements(&mut self, a: usize, b: usize) {
+        self.swap(a, b);
+    }
+}
+
+#[cfg(any(test, feature = "alloc"))]
+impl<T> Permutable for alloc::vec::Vec<T> {
+    fn swap_elements(&mut self, a: usize, b: usize) {
+        self.swap(a, b);
+    }
+}
+
+#[cfg(any(test, feature = "alloc"))]
+impl<T> Permutable for alloc::collections::VecDeque<T> {
+    fn swap_elements(&mut self, a: usize, b: usize) {
+        self.swap(a, b);
+    }
+}
+
+impl<P: Permutable + ?Sized> Permutable for &mut P {
+    fn swap_elements(&mut self, a: usize, b: usize) {
+        (**self).swap_elements(a, b);
+    }
+}
+
+macro_rules! impl_permutable_tuple {
+    ($($name:ident $index:tt),+) => {
+        /// Every member of the tuple is reordered in the same way
+        impl<$($name: Permutable),+> Permutable for ($($name,)+) {
+            fn swap_elements(&mut self, a: usize, b: usize) {
+                $(self.$index.swap_elements(a, b);)+
+            }
+        }
+    };
+}
+
+impl_permutable_tuple!(A 0, B 1);
+impl_permutable_tuple!(A 0, B 1, C 2);
+impl_permutable_tuple!(A 0, B 1, C 2, D 3);
+impl_permutable_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_permutable_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_permutable_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_permutable_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+macro_rules! impl_permutable_bits {
+    ($inner:ty) => {
+        /// The elements are the bits of the integer, starting with the least significant.
+        /// Swapping a bit past the width of the integer panics
+        impl Permutable for $inner {
+            fn swap_elements(&mut self, a: usize, b: usize) {
+                assert!(
+                    a < <$inner>::BITS as usize && b < <$inner>::BITS as usize,
+                    "bit index out of range for {}",
+                    stringify!($inner)
+                );
+                let diff = ((*self >> a) ^ (*self >> b)) & 1;
+                *self ^= (diff << a) | (diff << b);
+            }
+        }
+    };
+}
+
+impl_permutable_bits!(u8);
+impl_permutable_bits!(u16);
+impl_permutable_bits!(u32);
+impl_permutable_bits!(u64);
+impl_permutable_bits!(u128);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use itertools::Itertools;
+
+    use super::Permutable;
+    use crate::Permutation;
+
+    #[test]
+    pub fn test_containers() {
+        for perm in Permutation::<u8, 5>::all() {
+            let mut expected = [0, 1, 2, 3, 4, 5];
+            perm.apply(&mut expected);
+
+            assert_eq!([0, 1, 2, 3, 4, 5].permuted(&perm), expected);
+
+            let mut vec = (0..6).collect_vec();
+            perm.apply_to(&mut vec);
+            assert_eq!(vec, expected);
+
+            let mut deque: VecDeque<_> = (0..6).collect();
+            perm.apply_to(&mut deque);
+            assert_eq!(deque, expected);
+
+            perm.apply_inverse_to(&mut deque);
+            assert_eq!(deque, [0, 1, 2, 3, 4, 5]);
+        }
+    }
+
+    #[test]
+    pub fn test_columns() {
+        for perm in Permutation::<u8, 5>::all() {
+            let mut expected = [0, 1, 2, 3, 4];
+            perm.apply(&mut expected);
+
+            let mut xs = [0, 1, 2, 3, 4];
+            let mut ys = vec![0, 1, 2, 3, 4];
+            let mut names = ["0", "1", "2", "3", "4"];
+            perm.apply_to(&mut (&mut xs, &mut ys[..], &mut names));
+
+            assert_eq!(xs, expected);
+            assert_eq!(ys, expected);
+            assert_eq!(names, expected.map(|x| x.to_string()));
+
+            let mut columns = [[0, 1, 2, 3, 4]; 3];
+            perm.apply_all(&mut columns);
+            assert_eq!(columns, [expected; 3]);
+
+            perm.apply_inverse_all(&mut [&mut xs[..], &mut ys[..]]);
+            assert_eq!(xs, [0, 1, 2, 3, 4]);
+            assert_eq!(ys, [0, 1, 2, 3, 4]);
+        }
+    }
+
+    #[test]
+    pub fn test_bits() {
+        for perm in Permutation::<u16, 8>::all() {
+            let bits = 0b1011_0010u8;
+            let mut expected = [0u8; 8];
+            for (i, e) in expected.iter_mut().enumerate() {
+                *e = (bits >> i) & 1;
+            }
+            perm.apply(&mut expected);
+
+            let actual = bits.permuted(&perm);
+            for (i, e) in expected.into_iter().enumerate() {
+                assert_eq!((actual >> i) & 1, e);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "bit index out of range for u8")]
+    pub fn test_bits_too_short() {
+        let _ = 0u8.permuted(&Permutation::<u32, 9>::reverse());
+    }
+}
@@ -0,0 +1,254 @@
+use alloc::vec::Vec;
+use core::ops::Index;
+
+use crate::{inner::Inner, permutation_set::PermutationSet, Permutation};
+
+/// A map from permutations to values, stored as a slot for every possible permutation.
+/// Looking up a permutation is a single array access with no hashing.
+///
+/// The map has `n!` slots however many values it contains, so it is only suitable for small numbers of elements.
+/// ```
+/// use importunate::{Permutation, permutation_map::PermutationMap};
+/// let mut map = PermutationMap::<u8, 4, &str>::new();
+/// map.insert(Permutation::rotate_right(), "rotate");
+/// map.insert(Permutation::reverse(), "reverse");
+///
+/// assert_eq!(map.get(&Permutation::reverse()), Some(&"reverse"));
+/// assert_eq!(map.get(&Permutation::default()), None);
+/// assert_eq!(map[&Permutation::rotate_right()], "rotate");
+/// assert_eq!(map.len(), 2);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PermutationMap<I: Inner, const ELEMENTS: usize, V> {
+    values: Vec<Option<V>>,
+    len: usize,
+    phantom: core::marker::PhantomData<I>,
+}
+
+impl<I: Inner, const ELEMENTS: usize, V> Default for PermutationMap<I, ELEMENTS, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize, V> PermutationMap<I, ELEMENTS, V> {
+    /// Create an empty map
+    /// # Panics
+    ///
+    /// This will panic if there are more than `usize::MAX` permutations of this number of elements
+    pub fn new() -> Self {
+        let mut values = Vec::new();
+        values.resize_with(Permutation::<I, ELEMENTS>::table_len(), || None);
+        Self {
+            values,
+            len: 0,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Create a map with a value for every permutation
+    /// # Panics
+    ///
+    /// This will panic if there are more than `usize::MAX` permutations of this number of elements
+    pub fn from_fn(mut f: impl FnMut(Permutation<I, ELEMENTS>) -> V) -> Self {
+        let values: Vec<_> = Permutation::<I, ELEMENTS>::all()
+            .map(|perm| Some(f(perm)))
+            .collect();
+        Self {
+            len: values.len(),
+            values,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Insert a value for this permutation, returning the previous value if there was one
+    pub fn insert(&mut self, perm: Permutation<I, ELEMENTS>, value: V) -> Option<V> {
+        let previous = self.values[perm.table_index()].replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Remove the value for this permutation, returning it if there was one
+    pub fn remove(&mut self, perm: &Permutation<I, ELEMENTS>) -> Option<V> {
+        let previous = self.values[perm.table_index()].take();
+        if previous.is_some() {
+            self.len -= 1;
+        }
+        previous
+    }
+
+    /// Get the value for this permutation
+    #[must_use]
+    pub fn get(&self, perm: &Permutation<I, ELEMENTS>) -> Option<&V> {
+        self.values[perm.table_index()].as_ref()
+    }
+
+    /// Get a mutable reference to the value for this permutation
+    #[must_use]
+    pub fn get_mut(&mut self, perm: &Permutation<I, ELEMENTS>) -> Option<&mut V> {
+        self.values[perm.table_index()].as_mut()
+    }
+
+    /// Get the value for this permutation, inserting one made by `f` if there is none
+    pub fn get_or_insert_with(
+        &mut self,
+        perm: Permutation<I, ELEMENTS>,
+        f: impl FnOnce() -> V,
+    ) -> &mut V {
+        let slot = &mut self.values[perm.table_index()];
+        if slot.is_none() {
+            self.len += 1;
+        }
+        slot.get_or_insert_with(f)
+    }
+
+    /// Does the map contain a value for this permutation
+    #[must_use]
+    pub fn contains_key(&self, perm: &Permutation<I, ELEMENTS>) -> bool {
+        self.values[perm.table_index()].is_some()
+    }
+
+    /// The number of values in the map
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the map empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove every value from the map
+    pub fn clear(&mut self) {
+        self.values.fill_with(|| None);
+        self.len = 0;
+    }
+
+    /// Iterate through the permutations and values in the map, in order of permutation
+    pub fn iter(&self) -> impl Iterator<Item = (Permutation<I, ELEMENTS>, &V)> {
+        self.values.iter().enumerate().filter_map(|(index, value)| {
+            Some((Permutation::from_table_index(index), value.as_ref()?))
+        })
+    }
+
+    /// Iterate through the permutations and mutable values in the map, in order of permutation
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Permutation<I, ELEMENTS>, &mut V)> {
+        self.values
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, value)| {
+                Some((Permutation::from_table_index(index), value.as_mut()?))
+            })
+    }
+
+    /// Iterate through the permutations in the map, in order
+    pub fn keys(&self) -> impl Iterator<Item = Permutation<I, ELEMENTS>> + '_ {
+        self.iter().map(|(perm, _)| perm)
+    }
+
+    /// Iterate through the values in the map, in order of permutation
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.values.iter().flatten()
+    }
+
+    /// The set of permutations in the map
+    pub fn key_set(&self) -> PermutationSet<I, ELEMENTS> {
+        self.keys().collect()
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize, V> Index<&Permutation<I, ELEMENTS>>
+    for PermutationMap<I, ELEMENTS, V>
+{
+    type Output = V;
+
+    fn index(&self, perm: &Permutation<I, ELEMENTS>) -> &Self::Output {
+        match self.get(perm) {
+            Some(value) => value,
+            None => panic!("no value for permutation {perm}"),
+        }
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize, V> Extend<(Permutation<I, ELEMENTS>, V)>
+    for PermutationMap<I, ELEMENTS, V>
+{
+    fn extend<T: IntoIterator<Item = (Permutation<I, ELEMENTS>, V)>>(&mut self, iter: T) {
+        for (perm, value) in iter {
+            self.insert(perm, value);
+        }
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize, V> FromIterator<(Permutation<I, ELEMENTS>, V)>
+    for PermutationMap<I, ELEMENTS, V>
+{
+    fn from_iter<T: IntoIterator<Item = (Permutation<I, ELEMENTS>, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use arbtest::arbtest;
+    use itertools::Itertools;
+
+    use super::PermutationMap;
+    use crate::Permutation;
+
+    type Perm = Permutation<u8, 5>;
+
+    #[test]
+    pub fn test_map() {
+        arbtest(|u| {
+            let expected: BTreeMap<Perm, u16> = u.arbitrary()?;
+            let mut map: PermutationMap<u8, 5, u16> =
+                expected.iter().map(|(&k, &v)| (k, v)).collect();
+
+            assert_eq!(map.len(), expected.len());
+            assert_eq!(
+                map.iter().map(|(k, &v)| (k, v)).collect_vec(),
+                expected.iter().map(|(&k, &v)| (k, v)).collect_vec()
+            );
+            assert_eq!(map.key_set().len(), expected.len());
+            for perm in Perm::all() {
+                assert_eq!(map.get(&perm), expected.get(&perm));
+            }
+
+            for (perm, value) in map.iter_mut() {
+                *value = value.wrapping_add(u16::from(perm.get_array()[0]));
+            }
+            for (perm, value) in &expected {
+                let new_value = value.wrapping_add(u16::from(perm.get_array()[0]));
+                assert_eq!(map[perm], new_value);
+                assert_eq!(map.remove(perm), Some(new_value));
+            }
+            assert!(map.is_empty());
+            Ok(())
+        });
+    }
+
+    #[test]
+    pub fn test_from_fn() {
+        let mut map = PermutationMap::<u8, 4, _>::from_fn(|perm| perm.invert());
+        assert_eq!(map.len(), 24);
+        for (perm, inverse) in map.iter() {
+            assert!(perm.combine(inverse).is_default());
+        }
+
+        map.clear();
+        assert!(map.is_empty());
+        let value = map.get_or_insert_with(Permutation::reverse(), Permutation::reverse);
+        assert_eq!(*value, Permutation::reverse());
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -0,0 +1,272 @@
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::{inner::Inner, Permutation};
+
+/// A set of permutations, stored as one bit for every possible permutation.
+/// Inserting, removing and checking permutations is a single bit operation,
+/// and union and intersection work on 64 permutations at a time.
+///
+/// The set takes `n! / 8` bytes however many permutations it contains, so it is only suitable for small numbers of elements.
+/// ```
+/// use importunate::{Permutation, permutation_set::PermutationSet};
+/// let mut set = PermutationSet::<u8, 4>::new();
+/// assert!(set.insert(Permutation::rotate_right()));
+/// assert!(!set.insert(Permutation::rotate_right()));
+///
+/// assert!(set.contains(&Permutation::rotate_right()));
+/// assert!(!set.contains(&Permutation::reverse()));
+/// assert_eq!(set.len(), 1);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PermutationSet<I: Inner, const ELEMENTS: usize> {
+    words: Vec<u64>,
+    phantom: PhantomData<I>,
+}
+
+impl<I: Inner, const ELEMENTS: usize> Default for PermutationSet<I, ELEMENTS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize> core::fmt::Debug for PermutationSet<I, ELEMENTS> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize> PermutationSet<I, ELEMENTS> {
+    /// Create an empty set
+    /// # Panics
+    ///
+    /// This will panic if there are more than `usize::MAX` permutations of this number of elements
+    pub fn new() -> Self {
+        let words = Permutation::<I, ELEMENTS>::table_len().div_ceil(64);
+        Self {
+            words: vec![0; words],
+            phantom: PhantomData,
+        }
+    }
+
+    /// Create a set containing every permutation of this number of elements
+    /// # Panics
+    ///
+    /// This will panic if there are more than `usize::MAX` permutations of this number of elements
+    pub fn full() -> Self {
+        let mut set = Self::new();
+        set.words.fill(u64::MAX);
+        let extra = set.words.len() * 64 - Permutation::<I, ELEMENTS>::table_len();
+        if let Some(last) = set.words.last_mut() {
+            *last >>= extra;
+        }
+        set
+    }
+
+    /// Create a set from its words, where bit `i % 64` of word `i / 64` is the permutation with table index `i`
+    pub(crate) fn from_words(words: Vec<u64>) -> Self {
+        debug_assert_eq!(
+            words.len(),
+            Permutation::<I, ELEMENTS>::table_len().div_ceil(64)
+        );
+        Self {
+            words,
+            phantom: PhantomData,
+        }
+    }
+
+    fn position(perm: &Permutation<I, ELEMENTS>) -> (usize, u64) {
+        let index = perm.table_index();
+        (index / 64, 1 << (index % 64))
+    }
+
+    /// Add a permutation to the set.
+    /// Returns whether the permutation was newly inserted
+    pub fn insert(&mut self, perm: Permutation<I, ELEMENTS>) -> bool {
+        let (word, bit) = Self::position(&perm);
+        let inserted = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        inserted
+    }
+
+    /// Remove a permutation from the set.
+    /// Returns whether the permutation was present
+    pub fn remove(&mut self, perm: &Permutation<I, ELEMENTS>) -> bool {
+        let (word, bit) = Self::position(perm);
+        let removed = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        removed
+    }
+
+    /// Does the set contain this permutation
+    #[must_use]
+    pub fn contains(&self, perm: &Permutation<I, ELEMENTS>) -> bool {
+        let (word, bit) = Self::position(perm);
+        self.words[word] & bit != 0
+    }
+
+    /// The number of permutations in the set
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Is the set empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Remove every permutation from the set
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Iterate through the permutations in the set, in order
+    pub fn iter(&self) -> impl Iterator<Item = Permutation<I, ELEMENTS>> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, &word)| {
+                let mut remaining = word;
+                core::iter::from_fn(move || {
+                    if remaining == 0 {
+                        return None;
+                    }
+                    let bit = remaining.trailing_zeros() as usize;
+                    remaining &= remaining - 1;
+                    Some(word_index * 64 + bit)
+                })
+            })
+            .map(Permutation::from_table_index)
+    }
+
+    /// The permutations which are in either set
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.union_with(other);
+        result
+    }
+
+    /// The permutations which are in both sets
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+
+    /// The permutations which are in this set but not the other
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for (word, &other_word) in result.words.iter_mut().zip(other.words.iter()) {
+            *word &= !other_word;
+        }
+        result
+    }
+
+    /// Add every permutation in the other set to this set
+    pub fn union_with(&mut self, other: &Self) {
+        for (word, &other_word) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other_word;
+        }
+    }
+
+    /// Remove every permutation which is not in the other set from this set
+    pub fn intersect_with(&mut self, other: &Self) {
+        for (word, &other_word) in self.words.iter_mut().zip(other.words.iter()) {
+            *word &= other_word;
+        }
+    }
+
+    /// Is every permutation in this set also in the other set
+    #[must_use]
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .all(|(&word, &other_word)| word & !other_word == 0)
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize> Extend<Permutation<I, ELEMENTS>>
+    for PermutationSet<I, ELEMENTS>
+{
+    fn extend<T: IntoIterator<Item = Permutation<I, ELEMENTS>>>(&mut self, iter: T) {
+        for perm in iter {
+            self.insert(perm);
+        }
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize> FromIterator<Permutation<I, ELEMENTS>>
+    for PermutationSet<I, ELEMENTS>
+{
+    fn from_iter<T: IntoIterator<Item = Permutation<I, ELEMENTS>>>(iter: T) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use arbtest::arbtest;
+    use itertools::Itertools;
+
+    use super::PermutationSet;
+    use crate::Permutation;
+
+    type Perm = Permutation<u8, 5>;
+
+    #[test]
+    pub fn test_full() {
+        let full = PermutationSet::<u8, 5>::full();
+        assert_eq!(full.len(), 120);
+        assert_eq!(full.iter().collect_vec(), Perm::all().collect_vec());
+        assert!(PermutationSet::<u8, 5>::new().is_empty());
+        assert_eq!(PermutationSet::<u8, 3>::full().len(), 6);
+    }
+
+    #[test]
+    pub fn test_set_operations() {
+        arbtest(|u| {
+            let a: BTreeSet<Perm> = u.arbitrary()?;
+            let b: BTreeSet<Perm> = u.arbitrary()?;
+            let set_a: PermutationSet<u8, 5> = a.iter().copied().collect();
+            let set_b: PermutationSet<u8, 5> = b.iter().copied().collect();
+
+            assert_eq!(set_a.len(), a.len());
+            assert_eq!(set_a.iter().collect_vec(), a.iter().copied().collect_vec());
+            for perm in Perm::all() {
+                assert_eq!(set_a.contains(&perm), a.contains(&perm));
+            }
+
+            assert_eq!(
+                set_a.union(&set_b).iter().collect_vec(),
+                a.union(&b).copied().collect_vec()
+            );
+            assert_eq!(
+                set_a.intersection(&set_b).iter().collect_vec(),
+                a.intersection(&b).copied().collect_vec()
+            );
+            assert_eq!(
+                set_a.difference(&set_b).iter().collect_vec(),
+                a.difference(&b).copied().collect_vec()
+            );
+            assert_eq!(set_a.is_subset(&set_b), a.is_subset(&b));
+
+            let mut removed = set_a.clone();
+            for perm in &b {
+                assert_eq!(removed.remove(perm), a.contains(perm));
+            }
+            assert_eq!(removed, set_a.difference(&set_b));
+            Ok(())
+        });
+    }
+}
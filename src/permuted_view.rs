@@ -0,0 +1,182 @@
+use core::ops::Index;
+
+use crate::{inner::Inner, Permutation};
+
+/// A read only view of a slice, with the first `ELEMENTS` elements in permuted order.
+/// Elements are looked up as they are needed so nothing is copied or moved.
+/// ```
+/// use importunate::Permutation;
+/// let arr = ["zero", "one", "two", "three"];
+/// let perm = Permutation::<u8, 4>::calculate_unchecked([2, 0, 1, 3], |&x| x);
+/// let view = perm.view(&arr);
+///
+/// assert_eq!(view[0], "two");
+/// assert_eq!(view.iter().copied().collect::<Vec<_>>(), ["two", "zero", "one", "three"]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct PermutedView<'a, T, I: Inner, const ELEMENTS: usize> {
+    slice: &'a [T],
+    permutation: Permutation<I, ELEMENTS>,
+}
+
+// Not derived, because that would only copy views of elements which can be copied
+impl<T, I: Inner, const ELEMENTS: usize> Clone for PermutedView<'_, T, I, ELEMENTS> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, I: Inner, const ELEMENTS: usize> Copy for PermutedView<'_, T, I, ELEMENTS> {}
+
+impl<'a, T, I: Inner, const ELEMENTS: usize> PermutedView<'a, T, I, ELEMENTS> {
+    /// Create a view of the slice, permuted by the permutation.
+    /// # Panics
+    ///
+    /// This will panic if the slice has fewer than `ELEMENTS` elements
+    pub fn new(slice: &'a [T], permutation: Permutation<I, ELEMENTS>) -> Self {
+        assert!(
+            slice.len() >= ELEMENTS,
+            "slice length {} is less than {ELEMENTS}",
+            slice.len()
+        );
+        Self { slice, permutation }
+    }
+
+    /// The permutation used by this view
+    pub fn permutation(&self) -> Permutation<I, ELEMENTS> {
+        self.permutation
+    }
+
+    /// The number of elements in the view
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slice.len()
+    }
+
+    /// Is the view empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.slice.is_empty()
+    }
+
+    /// Get the element at the given index of the view, or `None` if the index is out of bounds
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        if index < ELEMENTS {
+            let old_index = self.permutation.element_at_index(index as u8, usize::from);
+            self.slice.get(old_index)
+        } else {
+            self.slice.get(index)
+        }
+    }
+
+    /// Iterate through the elements of the view in permuted order
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator {
+        let arr = self.permutation.get_array();
+        let slice = self.slice;
+        (0..slice.len()).map(move |index| match arr.get(index) {
+            Some(&old_index) => &slice[usize::from(old_index)],
+            None => &slice[index],
+        })
+    }
+}
+
+impl<T, I: Inner, const ELEMENTS: usize> Index<usize> for PermutedView<'_, T, I, ELEMENTS> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(element) => element,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {index}",
+                self.len()
+            ),
+        }
+    }
+}
+
+/// Extension methods for collecting iterators in permuted order
+pub trait PermuteIterator: Iterator + Sized {
+    /// Collect the first `ELEMENTS` items of this iterator into an array, reordered by the permutation.
+    /// This will return `None` if the iterator has fewer than `ELEMENTS` items
+    /// ```
+    /// use importunate::{Permutation, permuted_view::PermuteIterator};
+    /// let perm = Permutation::<u8, 4>::calculate_unchecked([2, 0, 1, 3], |&x| x);
+    /// let arr = ["zero", "one", "two", "three"].into_iter().permute(perm);
+    ///
+    /// assert_eq!(arr, Some(["two", "zero", "one", "three"]));
+    /// ```
+    fn permute<I: Inner, const ELEMENTS: usize>(
+        mut self,
+        permutation: Permutation<I, ELEMENTS>,
+    ) -> Option<[Self::Item; ELEMENTS]> {
+        let items = [(); ELEMENTS].map(|()| self.next());
+        if items.iter().any(Option::is_none) {
+            return None;
+        }
+
+        let mut arr = items.map(|item| item.unwrap_or_else(|| unreachable!()));
+        permutation.apply(&mut arr);
+        Some(arr)
+    }
+}
+
+impl<It: Iterator> PermuteIterator for It {}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::PermuteIterator;
+    use crate::Permutation;
+
+    #[test]
+    pub fn test_view() {
+        let arr = [10, 11, 12, 13, 14, 15];
+        for perm in Permutation::<u8, 4>::all() {
+            let mut expected = arr;
+            perm.apply(&mut expected);
+
+            let view = perm.view(&arr);
+            assert_eq!(view.len(), 6);
+
+            let indexed = (0..view.len()).map(|i| view[i]).collect_vec();
+            assert_eq!(indexed, expected);
+
+            let iterated = view.iter().copied().collect_vec();
+            assert_eq!(iterated, expected);
+
+            let reversed = view.iter().rev().copied().collect_vec();
+            assert_eq!(reversed, expected.into_iter().rev().collect_vec());
+
+            assert_eq!(view.get(6), None);
+        }
+    }
+
+    #[test]
+    pub fn test_copy_view() {
+        let arr = ["zero".to_string(), "one".to_string()];
+        let view = Permutation::<u8, 2>::reverse().view(&arr);
+        let copy = view;
+        assert_eq!(view[0], copy[0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "less than 4")]
+    pub fn test_view_too_short() {
+        let _ = Permutation::<u8, 4>::default().view(&[0, 1, 2]);
+    }
+
+    #[test]
+    pub fn test_permute() {
+        for perm in Permutation::<u8, 5>::all() {
+            let mut expected = [0, 1, 2, 3, 4];
+            perm.apply(&mut expected);
+
+            assert_eq!((0..5).permute(perm), Some(expected));
+            assert_eq!((0..10).permute(perm), Some(expected));
+            assert_eq!((0..4).permute(perm), None);
+        }
+    }
+}
@@ -1,7 +1,34 @@
-use crate::{inner::Inner, Permutation};
+use alloc::{
+    collections::{btree_map::Entry, BTreeMap},
+    vec,
+    vec::Vec,
+};
+use core::{
+    marker::PhantomData,
+    ops::Range,
+    sync::atomic::{AtomicU64, AtomicU8, Ordering},
+};
+#[cfg(any(test, feature = "std"))]
+use std::io::{self, Read, Write};
+
+use crate::{
+    cayley_table::CayleyTable, inner::Inner, permutation_set::PermutationSet,
+    stabilizer_chain::StabilizerChain, Permutation,
+};
+
+/// The first bytes of a saved `SolveContext`
+#[cfg(any(test, feature = "std"))]
+const MAGIC: [u8; 8] = *b"IMPSOLVE";
+/// The version of the saved `SolveContext` format
+#[cfg(any(test, feature = "std"))]
+const FORMAT_VERSION: u8 = 2;
 
 /// Can be used to solve a permutation, finding the the shortest combination of a fixed set of permutations that leads to it
-pub struct SolveContext<I: Inner, const ELEMENTS: usize> {
+///
+/// The table is usually a `Vec<u8>`, but a saved solver can be read from any bytes with `from_bytes`.
+/// Use this to solve with a memory mapped file instead of copying the table into memory
+#[derive(Clone, PartialEq, Eq)]
+pub struct SolveContext<I: Inner, const ELEMENTS: usize, T: AsRef<[u8]> = Vec<u8>> {
     /// Every index contains four pairs of bits
     /// Each permutation is associated with a pair
     /// Permutation p is associated with pair (p % 4) at index (p / 4)
@@ -10,44 +37,316 @@ pub struct SolveContext<I: Inner, const ELEMENTS: usize> {
     /// 01: p is 1 mod 3 steps from solved
     /// 10: p is 2 mod 3 steps from solved
     /// 11: p cannot be solved with this set of moves
-    vec: Vec<u8>,
+    table: T,
 
     /// The total number of solvable permutations
     pub number_solvable: I,
 
     moves: Vec<Permutation<I, ELEMENTS>>,
+
+    /// The number of permutations at each distance from solved
+    distance_histogram: Vec<I>,
+
+    /// The permutations at the maximum distance from solved
+    antipodes: Vec<Permutation<I, ELEMENTS>>,
+
+    /// The exact distance of each permutation from solved, or `u8::MAX` if it cannot be solved.
+    /// Only recorded by `new_with_distances`
+    distances: Option<Vec<u8>>,
 }
 
-impl<I: Inner, const ELEMENTS: usize> SolveContext<I, ELEMENTS> {
-    /// Try to Deconstruct the inverse of this permutation into the shortest sequence of permutations from the allowed list
-    pub fn solve(
+/// The table, antipodes and distances can be very large, so only their lengths are shown
+impl<I: Inner, const ELEMENTS: usize, T: AsRef<[u8]>> core::fmt::Debug
+    for SolveContext<I, ELEMENTS, T>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SolveContext")
+            .field("table_len", &self.table.as_ref().len())
+            .field("number_solvable", &self.number_solvable)
+            .field("moves", &self.moves)
+            .field("distance_histogram", &self.distance_histogram)
+            .field("antipodes_len", &self.antipodes.len())
+            .field("distances_len", &self.distances.as_ref().map(Vec::len))
+            .finish()
+    }
+}
+
+/// An iterator over every shortest sequence of moves which solves a permutation.
+/// Created by `SolveContext::all_shortest_solutions`
+#[derive(Clone, Debug)]
+pub struct ShortestSolutions<'a, I: Inner, const ELEMENTS: usize, T: AsRef<[u8]> = Vec<u8>> {
+    context: &'a SolveContext<I, ELEMENTS, T>,
+    /// The permutations along the current path and the index of the next move to try from each
+    stack: Vec<(Permutation<I, ELEMENTS>, usize)>,
+    /// The moves along the current path
+    path: Vec<Permutation<I, ELEMENTS>>,
+}
+
+impl<I: Inner, const ELEMENTS: usize, T: AsRef<[u8]>> Iterator
+    for ShortestSolutions<'_, I, ELEMENTS, T>
+{
+    type Item = Vec<Permutation<I, ELEMENTS>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (perm, move_index) = self.stack.last_mut()?;
+            if perm.is_default() {
+                let solution = self.path.clone();
+                self.stack.pop();
+                self.path.pop();
+                return Some(solution);
+            }
+
+            // Every move which leads one step closer to solved is on a shortest path.
+            // A corrupted table could lead round in circles, so never go deeper than the maximum distance
+            let next = (self.context.get_bits(*perm) + 2) % 3;
+            let found = self.context.moves[*move_index..]
+                .iter()
+                .enumerate()
+                .map(|(i, m)| (i, m, perm.combine(m)))
+                .find(|(_, _, combined)| self.context.get_bits(*combined) == next)
+                .filter(|_| self.path.len() < self.context.max_distance());
+
+            if let Some((i, m, combined)) = found {
+                *move_index += i + 1;
+                self.path.push(*m);
+                self.stack.push((combined, 0));
+            } else {
+                self.stack.pop();
+                self.path.pop();
+            }
+        }
+    }
+}
+
+/// The parts of a saved solver other than its table
+#[cfg(any(test, feature = "std"))]
+struct SavedParts<I: Inner, const ELEMENTS: usize> {
+    moves: Vec<Permutation<I, ELEMENTS>>,
+    number_solvable: u64,
+    distance_histogram: Vec<u64>,
+    antipodes: Vec<Permutation<I, ELEMENTS>>,
+}
+
+impl<I: Inner, const ELEMENTS: usize, T: AsRef<[u8]>> SolveContext<I, ELEMENTS, T> {
+    /// Try to Deconstruct the inverse of this permutation into the shortest sequence of permutations from the allowed list.
+    /// Combining the permutation with each of the moves in turn gives the default permutation.
+    /// Returns `None` if the moves cannot solve it, or if the table of a loaded solver is corrupted.
+    /// This is the same as `solve_to_identity`
+    pub fn solve(&self, perm: Permutation<I, ELEMENTS>) -> Option<Vec<Permutation<I, ELEMENTS>>> {
+        self.solve_by(perm, Permutation::combine)
+    }
+
+    /// Try to find the shortest sequence of moves which leads from this permutation to the default permutation
+    pub fn solve_to_identity(
         &self,
-        mut perm: Permutation<I, ELEMENTS>,
+        perm: Permutation<I, ELEMENTS>,
+    ) -> Option<Vec<Permutation<I, ELEMENTS>>> {
+        self.solve(perm)
+    }
+
+    /// Try to find the shortest sequence of moves which leads from the default permutation to this permutation.
+    /// Combining these moves in order gives the permutation
+    pub fn generate_from_identity(
+        &self,
+        perm: Permutation<I, ELEMENTS>,
+    ) -> Option<Vec<Permutation<I, ELEMENTS>>> {
+        self.solve(perm.invert())
+    }
+
+    /// Try to find the shortest sequence of moves which leads from one permutation to another
+    /// ```
+    /// use importunate::{Permutation, solver::SolveContext};
+    /// let context = SolveContext::<u8, 5>::new(vec![Permutation::rotate_right(), Permutation::reverse()]);
+    /// let from = Permutation::interleave(2);
+    /// let to = from.combine(&Permutation::reverse());
+    ///
+    /// let path = context.path(from, to).unwrap();
+    /// assert_eq!(path, [Permutation::reverse()]);
+    /// assert!(context.is_path(from, &path, to));
+    /// ```
+    pub fn path(
+        &self,
+        from: Permutation<I, ELEMENTS>,
+        to: Permutation<I, ELEMENTS>,
+    ) -> Option<Vec<Permutation<I, ELEMENTS>>> {
+        // The moves must combine to the permutation which takes `from` to `to`
+        self.generate_from_identity(from.invert().combine(&to))
+    }
+
+    /// Whether this sequence of moves, all of which are allowed, leads from one permutation to another
+    #[must_use]
+    pub fn is_path(
+        &self,
+        from: Permutation<I, ELEMENTS>,
+        moves: &[Permutation<I, ELEMENTS>],
+        to: Permutation<I, ELEMENTS>,
+    ) -> bool {
+        moves.iter().all(|m| self.moves.binary_search(m).is_ok())
+            && moves.iter().fold(from, |perm, m| perm.combine(m)) == to
+    }
+
+    /// Try to Deconstruct the inverse of this permutation into the shortest sequence of permutations from the allowed list.
+    /// This uses the table to combine permutations, which is faster for small numbers of elements
+    pub fn solve_with_table(
+        &self,
+        perm: Permutation<I, ELEMENTS>,
+        table: &CayleyTable<I, ELEMENTS>,
+    ) -> Option<Vec<Permutation<I, ELEMENTS>>> {
+        self.solve_by(perm, |lhs, rhs| table.combine(lhs, rhs))
+    }
+
+    fn solve_by(
+        &self,
+        perm: Permutation<I, ELEMENTS>,
+        combine: impl Fn(
+            &Permutation<I, ELEMENTS>,
+            &Permutation<I, ELEMENTS>,
+        ) -> Permutation<I, ELEMENTS>,
     ) -> Option<Vec<Permutation<I, ELEMENTS>>> {
         let mut result = vec![];
+        self.walk(perm, combine, |m| result.push(*m))
+            .then_some(result)
+    }
+
+    /// Every shortest sequence of moves which solves this permutation, in order of their moves.
+    /// The first of these is the sequence returned by `solve`.
+    /// There are no sequences if the permutation cannot be solved
+    /// ```
+    /// use importunate::{Permutation, solver::SolveContext};
+    /// let rotate = Permutation::<u8, 4>::rotate_right();
+    /// let context = SolveContext::new(vec![rotate]);
+    ///
+    /// // Rotating twice can be undone by rotating twice in either direction
+    /// let solutions: Vec<_> = context.all_shortest_solutions(rotate.combine(&rotate)).collect();
+    /// assert_eq!(solutions.len(), 2);
+    /// assert!(solutions.contains(&vec![rotate; 2]));
+    /// assert!(solutions.contains(&vec![rotate.invert(); 2]));
+    /// ```
+    #[must_use]
+    pub fn all_shortest_solutions(
+        &self,
+        perm: Permutation<I, ELEMENTS>,
+    ) -> ShortestSolutions<'_, I, ELEMENTS, T> {
+        let stack = if self.get_bits(perm) == 3 {
+            vec![]
+        } else {
+            vec![(perm, 0)]
+        };
+        ShortestSolutions {
+            context: self,
+            stack,
+            path: vec![],
+        }
+    }
+
+    /// The number of shortest sequences of moves which solve this permutation, or `None` if it cannot be solved.
+    /// This is much faster than counting `all_shortest_solutions`, and saturates at `u128::MAX`
+    #[must_use]
+    pub fn count_shortest_solutions(&self, perm: Permutation<I, ELEMENTS>) -> Option<u128> {
         let mut moves_mod_3 = self.get_bits(perm);
         if moves_mod_3 == 3 {
             return None;
         }
 
+        // The number of shortest paths from `perm` to each permutation in the current layer
+        let mut layer = BTreeMap::from([(perm, 1u128)]);
+        for _ in 0..=self.max_distance() {
+            if let Some(&count) = layer.get(&Permutation::default()) {
+                return Some(count);
+            }
+            moves_mod_3 = (moves_mod_3 + 2) % 3;
+            let mut next_layer = BTreeMap::new();
+            for (p, count) in layer {
+                for m in &self.moves {
+                    let combined = p.combine(m);
+                    if self.get_bits(combined) == moves_mod_3 {
+                        let total: &mut u128 = next_layer.entry(combined).or_default();
+                        *total = total.saturating_add(count);
+                    }
+                }
+            }
+            layer = next_layer;
+        }
+
+        // Only a corrupted table has no path within the maximum distance
+        None
+    }
+
+    /// The number of moves needed to solve this permutation, or `None` if it cannot be solved.
+    ///
+    /// This is a single lookup for a solver made with `new_with_distances`.
+    /// Otherwise the table only stores each distance mod 3,
+    /// so this follows the shortest path to solved and takes time proportional to the distance times the number of moves
+    #[must_use]
+    pub fn distance(&self, perm: Permutation<I, ELEMENTS>) -> Option<usize> {
+        if let Some(distances) = &self.distances {
+            let distance = distances[perm.table_index()];
+            return (distance != u8::MAX).then_some(usize::from(distance));
+        }
+        let mut distance = 0;
+        self.walk(perm, Permutation::combine, |_| distance += 1)
+            .then_some(distance)
+    }
+
+    /// The largest number of moves needed to solve any solvable permutation.
+    /// This is the diameter of the Cayley graph of the moves, sometimes called God's number
+    #[must_use]
+    pub fn max_distance(&self) -> usize {
+        self.distance_histogram.len() - 1
+    }
+
+    /// The number of permutations at each distance from solved, starting with the default permutation at distance zero
+    pub fn distance_histogram(&self) -> &[I] {
+        &self.distance_histogram
+    }
+
+    /// The permutations which need the most moves to solve
+    pub fn antipodes(&self) -> &[Permutation<I, ELEMENTS>] {
+        &self.antipodes
+    }
+
+    /// Follow the shortest path from this permutation to solved, calling `on_move` with each move.
+    /// Returns whether the permutation could be solved.
+    /// A corrupted table may have no path to solved, or a path that goes round in circles,
+    /// so this gives up if there is no next move or the path is longer than `max_distance`
+    fn walk(
+        &self,
+        mut perm: Permutation<I, ELEMENTS>,
+        combine: impl Fn(
+            &Permutation<I, ELEMENTS>,
+            &Permutation<I, ELEMENTS>,
+        ) -> Permutation<I, ELEMENTS>,
+        mut on_move: impl FnMut(&Permutation<I, ELEMENTS>),
+    ) -> bool {
+        let mut moves_mod_3 = self.get_bits(perm);
+        if moves_mod_3 == 3 {
+            return false;
+        }
+
+        let mut steps = 0;
         'outer: while !perm.is_default() {
+            if steps == self.max_distance() {
+                return false;
+            }
+            steps += 1;
             let next = (moves_mod_3 + 2) % 3;
 
             for m in &self.moves {
-                let combined = perm.combine(m);
+                let combined = combine(&perm, m);
                 let mm3 = self.get_bits(combined);
                 if mm3 == next {
                     perm = combined;
-                    result.push(*m);
+                    on_move(m);
                     moves_mod_3 = next;
                     continue 'outer;
                 }
             }
 
-            unreachable!()
+            return false;
         }
 
-        Some(result)
+        true
     }
 
     fn get_bits(&self, perm: Permutation<I, ELEMENTS>) -> u8 {
@@ -55,9 +354,155 @@ impl<I: Inner, const ELEMENTS: usize> SolveContext<I, ELEMENTS> {
         let index = us / 4usize;
 
         let shift = (us % 4) * 2;
-        3u8 & (self.vec[index] >> shift)
+        3u8 & (self.table.as_ref()[index] >> shift)
     }
 
+    /// Add the inverses of the moves and put them in a consistent order
+    fn prepare_moves(moves: &mut Vec<Permutation<I, ELEMENTS>>) {
+        for m in &moves.clone() {
+            moves.push(m.invert());
+        }
+        moves.sort();
+        moves.dedup();
+    }
+
+    /// The number of bytes needed to store a pair of bits for each permutation
+    fn table_len(total: usize) -> usize {
+        total.div_ceil(4)
+    }
+
+    /// The moves this solver uses, including their inverses
+    pub fn moves(&self) -> &[Permutation<I, ELEMENTS>] {
+        &self.moves
+    }
+
+    /// Write this solver so it can be loaded with `read_from` instead of being recalculated.
+    ///
+    /// The format is the magic bytes `IMPSOLVE`, a version byte and the number of elements as a byte, followed by
+    /// the moves, the number of solvable permutations, the distance histogram, the antipodes and the table.
+    /// Numbers are little endian `u64`s. Lists start with their length and permutations take `REQUIRED_BYTES` bytes.
+    /// # Errors
+    ///
+    /// Returns any error from the writer
+    #[cfg(any(test, feature = "std"))]
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION, ELEMENTS as u8])?;
+
+        write_permutations(&mut writer, &self.moves)?;
+        write_count(&mut writer, self.number_solvable)?;
+        write_usize(&mut writer, self.distance_histogram.len())?;
+        for &count in &self.distance_histogram {
+            write_count(&mut writer, count)?;
+        }
+        write_permutations(&mut writer, &self.antipodes)?;
+
+        let table = self.table.as_ref();
+        write_usize(&mut writer, table.len())?;
+        writer.write_all(table)?;
+        Ok(())
+    }
+
+    /// Read everything before the table from a saved solver.
+    /// Returns the saved parts and the length of the table
+    #[cfg(any(test, feature = "std"))]
+    fn read_header<R: Read>(reader: &mut R) -> io::Result<(SavedParts<I, ELEMENTS>, usize)> {
+        let mut header = [0u8; MAGIC.len() + 2];
+        reader.read_exact(&mut header)?;
+        if header[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data("Not a saved SolveContext"));
+        }
+        let [version, elements] = [header[MAGIC.len()], header[MAGIC.len() + 1]];
+        if version != FORMAT_VERSION {
+            return Err(invalid_data("Unsupported SolveContext format version"));
+        }
+        if usize::from(elements) != ELEMENTS {
+            return Err(invalid_data(
+                "Saved SolveContext has a different number of elements",
+            ));
+        }
+
+        let moves = read_permutations(reader)?;
+        let number_solvable = read_u64(reader)?;
+        let distance_histogram = (0..read_u64(reader)?)
+            .map(|_| read_u64(reader))
+            .collect::<io::Result<_>>()?;
+        let antipodes = read_permutations(reader)?;
+
+        // Check the length before anything is allocated for the table
+        let Ok(total) = I::get_factorial(ELEMENTS).try_into() else {
+            return Err(invalid_data("Cannot solve for this many elements"));
+        };
+        let table_len = Self::table_len(total);
+        if read_u64(reader)? != table_len as u64 {
+            return Err(invalid_data("Saved table has the wrong length"));
+        }
+
+        let parts = SavedParts {
+            moves,
+            number_solvable,
+            distance_histogram,
+            antipodes,
+        };
+        Ok((parts, table_len))
+    }
+
+    /// Check that the parts of a saved solver are consistent with each other and with the moves, and combine them
+    #[cfg(any(test, feature = "std"))]
+    fn from_parts(
+        table: T,
+        parts: SavedParts<I, ELEMENTS>,
+        mut moves: Vec<Permutation<I, ELEMENTS>>,
+    ) -> io::Result<Self> {
+        Self::prepare_moves(&mut moves);
+        if moves != parts.moves {
+            return Err(invalid_data(
+                "Saved SolveContext was created with different moves",
+            ));
+        }
+
+        let Ok(total) = I::get_factorial(ELEMENTS).try_into() else {
+            return Err(invalid_data("Cannot solve for this many elements"));
+        };
+        if table.as_ref().len() != Self::table_len(total) {
+            return Err(invalid_data("Saved table has the wrong length"));
+        }
+
+        let to_count = |n: u64| {
+            usize::try_from(n)
+                .ok()
+                .filter(|&n| n <= total)
+                .and_then(|n| I::try_from(n).ok())
+                .ok_or_else(|| invalid_data("Saved number of permutations is out of range"))
+        };
+        let number_solvable = to_count(parts.number_solvable)?;
+        let distance_histogram = parts
+            .distance_histogram
+            .iter()
+            .map(|&count| to_count(count))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let saved_histogram = &parts.distance_histogram;
+        if saved_histogram.first() != Some(&1)
+            || saved_histogram.last() != Some(&(parts.antipodes.len() as u64))
+            || saved_histogram.iter().map(|&c| u128::from(c)).sum::<u128>()
+                != u128::from(parts.number_solvable)
+        {
+            return Err(invalid_data("Saved distance histogram is inconsistent"));
+        }
+
+        Ok(Self {
+            table,
+            number_solvable,
+            moves,
+            distance_histogram,
+            antipodes: parts.antipodes,
+            distances: None,
+        })
+    }
+}
+
+impl<I: Inner, const ELEMENTS: usize> SolveContext<I, ELEMENTS> {
     fn combine_arrays(lhs: &[u8; ELEMENTS], rhs_swaps: &[u8; ELEMENTS]) -> [u8; ELEMENTS] {
         let mut result = *lhs;
 
@@ -67,68 +512,596 @@ impl<I: Inner, const ELEMENTS: usize> SolveContext<I, ELEMENTS> {
         result
     }
 
-    /// Create a new solver from a fixed set of moves. This will also use the inverses of those moves
+    /// Find the shortest sequence of moves which solves a single permutation without building a table.
+    /// This will also use the inverses of the moves.
+    ///
+    /// This searches forwards from the permutation and backwards from solved until the searches meet,
+    /// so it only visits the permutations which are about half as far from either end.
+    /// Use this for one-off queries with too many elements for a table to fit in memory
+    /// ```
+    /// use importunate::{Permutation, solver::SolveContext};
+    /// let moves = vec![Permutation::<u64, 14>::rotate_right(), Permutation::reverse()];
+    /// let perm = Permutation::rotate_left().combine(&Permutation::reverse());
+    ///
+    /// let solution = SolveContext::solve_once(moves, perm).unwrap();
+    /// assert_eq!(solution.len(), 2);
+    /// assert_eq!(solution.iter().fold(perm, |p, m| p.combine(m)), Permutation::default());
+    /// ```
+    #[must_use]
+    pub fn solve_once(
+        mut moves: Vec<Permutation<I, ELEMENTS>>,
+        perm: Permutation<I, ELEMENTS>,
+    ) -> Option<Vec<Permutation<I, ELEMENTS>>> {
+        Self::prepare_moves(&mut moves);
+        let inverses = moves.iter().map(Permutation::invert).collect::<Vec<_>>();
+        let identity = Permutation::<I, ELEMENTS>::default();
+
+        // The index of the move which first reached each permutation in each search
+        let mut forward = BTreeMap::from([(perm, None)]);
+        let mut backward = BTreeMap::from([(identity, None)]);
+        let mut forward_frontier = vec![perm];
+        let mut backward_frontier = vec![identity];
+
+        // Until the searches meet, no path is shorter than the sum of their depths,
+        // so the first permutation reached by both is on a shortest path
+        let mut meeting = (perm == identity).then_some(perm);
+        while meeting.is_none() {
+            if forward_frontier.is_empty() || backward_frontier.is_empty() {
+                return None;
+            }
+            meeting = if forward_frontier.len() <= backward_frontier.len() {
+                search_layer(&mut forward_frontier, &mut forward, &backward, &moves)
+            } else {
+                search_layer(&mut backward_frontier, &mut backward, &forward, &inverses)
+            };
+        }
+        let meeting = meeting?;
+
+        let mut solution = vec![];
+        let mut current = meeting;
+        while let Some(index) = forward[&current] {
+            solution.push(moves[index]);
+            current = current.combine(&inverses[index]);
+        }
+        solution.reverse();
+
+        let mut current = meeting;
+        while let Some(index) = backward[&current] {
+            solution.push(moves[index]);
+            current = current.combine(&moves[index]);
+        }
+        Some(solution)
+    }
+
+    /// Create a new solver like `new`, finding the permutations at each distance from solved in parallel.
+    /// The solver is identical to the one made by `new`
     /// # Panics
     ///
     /// This will panic if the number of possible solutions is greater than `usize::MAX`
+    #[cfg(any(test, feature = "rayon"))]
     #[must_use]
-    pub fn new(mut moves: Vec<Permutation<I, ELEMENTS>>) -> Self {
+    pub fn new_parallel(moves: Vec<Permutation<I, ELEMENTS>>) -> Self
+    where
+        I: Send + Sync,
+    {
+        use rayon::prelude::*;
+        let join = |(count, mut layer): (usize, Vec<_>), (other_count, other_layer)| {
+            layer.extend(other_layer);
+            (count + other_count, layer)
+        };
+        Self::build(moves, false, |builder, level, frontier| {
+            if let Some(frontier) = frontier {
+                // Each task pushes from as many permutations as there are in a chunk of the table
+                return frontier
+                    .par_chunks(PARALLEL_CHUNK_BYTES * 4)
+                    .map(|chunk| builder.scan(chunk.iter().map(Permutation::table_index), level))
+                    .reduce(|| (0, vec![]), join);
+            }
+            let len = builder.table.len();
+            (0..len.div_ceil(PARALLEL_CHUNK_BYTES))
+                .into_par_iter()
+                .map(|chunk| {
+                    let start = chunk * PARALLEL_CHUNK_BYTES;
+                    builder.scan(
+                        builder.indices(start..len.min(start + PARALLEL_CHUNK_BYTES)),
+                        level,
+                    )
+                })
+                .reduce(|| (0, vec![]), join)
+        })
+    }
+
+    /// Build the table one distance from solved at a time, scanning it with `scan_level`.
+    /// When the permutations at the last distance were collected, `scan_level` is given them to push from instead of the whole table.
+    /// `scan_level` returns the number of permutations it set and, if asked to collect them, the permutations.
+    /// If `record_distances` is set, the exact distance of each permutation is stored as it is found
+    fn build(
+        mut moves: Vec<Permutation<I, ELEMENTS>>,
+        record_distances: bool,
+        scan_level: impl Fn(
+            &TableBuilder<I, ELEMENTS>,
+            Level,
+            Option<&[Permutation<I, ELEMENTS>]>,
+        ) -> (usize, Vec<Permutation<I, ELEMENTS>>),
+    ) -> Self {
         let Ok(total) = I::get_factorial(ELEMENTS).try_into() else{
             panic!("Cannot solve for {ELEMENTS} elements!");
         };
 
-        for m in &moves.clone() {
-            moves.push(m.invert());
+        Self::prepare_moves(&mut moves);
+        let mut builder = TableBuilder::new(total, &moves, record_distances);
+        let solved = Permutation::<I, ELEMENTS>::default();
+        builder.set(solved.table_index(), 0);
+
+        // The moves may only generate a subgroup, so count the permutations which can be solved up front
+        let reachable: usize = StabilizerChain::new(&moves)
+            .order()
+            .try_into()
+            .unwrap_or_else(|_| unreachable!());
+
+        let mut number_solvable = 1usize;
+        let mut distance_histogram = vec![1usize];
+        // The permutations at the last distance, if there were few enough to collect
+        let mut antipodes = Some(vec![solved]);
+
+        // Only keep the latest permutations when there cannot be too many of them
+        let collect_limit =
+            Self::table_len(total) / core::mem::size_of::<Permutation<I, ELEMENTS>>();
+
+        while number_solvable < reachable {
+            // Pulling checks every unset permutation, including those which cannot be solved
+            let unsolved = total - number_solvable;
+            // Pushing while scanning the table also pushes from every third distance before the last
+            let push_work: usize = antipodes.as_ref().map_or_else(
+                || distance_histogram.iter().rev().step_by(3).sum(),
+                Vec::len,
+            );
+            // Each permutation at the last distance has at most one new neighbour per move
+            let next_bound = (reachable - number_solvable)
+                .min(distance_histogram[distance_histogram.len() - 1].saturating_mul(moves.len()));
+            let level = Level {
+                distance: distance_histogram.len() - 1,
+                pull: unsolved < push_work,
+                collect: next_bound <= collect_limit,
+            };
+
+            assert!(
+                !record_distances || level.distance + 1 < usize::from(u8::MAX),
+                "Distances are too large to record"
+            );
+            if !level.collect {
+                builder.clear_layer();
+            }
+            let frontier = antipodes.as_deref().filter(|_| !level.pull);
+            let (count, layer) = scan_level(&builder, level, frontier);
+            if count == 0 {
+                break;
+            }
+            number_solvable += count;
+            distance_histogram.push(count);
+            antipodes = level.collect.then_some(layer);
         }
-        moves.sort();
-        moves.dedup();
 
-        let mut vec = vec![u8::MAX; total];
+        // Too many permutations at the last distance to collect were marked in a set instead
+        let mut antipodes = antipodes.unwrap_or_else(|| builder.layer().iter().collect());
+        antipodes.sort();
+
+        let distances = record_distances.then(|| builder.take_distances());
+        Self {
+            table: builder.into_table(),
+            number_solvable: I::try_from(number_solvable).unwrap_or_else(|_| unreachable!()),
+            moves,
+            distance_histogram: distance_histogram
+                .into_iter()
+                .map(|count| I::try_from(count).unwrap_or_else(|_| unreachable!()))
+                .collect(),
+            antipodes,
+            distances,
+        }
+    }
+
+    /// Read a solver which was written with `write_to`.
+    /// The moves must be the moves the solver was created with, though they may be in a different order.
+    /// # Errors
+    ///
+    /// Returns an error with kind `InvalidData` if the data is not a saved solver for these moves,
+    /// or any error from the reader
+    #[cfg(any(test, feature = "std"))]
+    pub fn read_from<R: Read>(
+        mut reader: R,
+        moves: Vec<Permutation<I, ELEMENTS>>,
+    ) -> io::Result<Self> {
+        let (parts, table_len) = Self::read_header(&mut reader)?;
+        let mut table = vec![0u8; table_len];
+        reader.read_exact(&mut table)?;
+
+        Self::from_parts(table, parts, moves)
+    }
+
+    /// Create a new solver from a fixed set of moves. This will also use the inverses of those moves.
+    /// For moves which cannot be undone, use a `DirectedSolveContext` instead.
+    ///
+    /// This finds the permutations at each distance from solved by scanning the table rather than storing them,
+    /// unless there are few of them, so it needs little memory beyond the table itself
+    /// # Panics
+    ///
+    /// This will panic if the number of possible solutions is greater than `usize::MAX`
+    #[must_use]
+    pub fn new(moves: Vec<Permutation<I, ELEMENTS>>) -> Self {
+        Self::build_sequential(moves, false)
+    }
+
+    /// Create a new solver like `new` which also records the exact distance of every permutation from solved,
+    /// so `distance` is a single lookup instead of following the path to solved.
+    /// The distances take a byte for each permutation, four times the memory of the table.
+    ///
+    /// The distances are not saved by `write_to`, so a loaded solver does not have them
+    /// ```
+    /// use importunate::{Permutation, solver::SolveContext};
+    /// let moves = vec![Permutation::<u8, 5>::rotate_right(), Permutation::reverse()];
+    /// let context = SolveContext::new_with_distances(moves.clone());
+    ///
+    /// let perm = Permutation::rotate_left().combine(&Permutation::reverse());
+    /// assert_eq!(context.distance(perm), Some(2));
+    /// assert_eq!(context.distance(perm), SolveContext::new(moves).distance(perm));
+    /// ```
+    /// # Panics
+    ///
+    /// This will panic if the number of possible solutions is greater than `usize::MAX`,
+    /// or if any permutation is 255 or more moves from solved
+    #[must_use]
+    pub fn new_with_distances(moves: Vec<Permutation<I, ELEMENTS>>) -> Self {
+        Self::build_sequential(moves, true)
+    }
+
+    fn build_sequential(moves: Vec<Permutation<I, ELEMENTS>>, record_distances: bool) -> Self {
+        Self::build(
+            moves,
+            record_distances,
+            |builder, level, frontier| match frontier {
+                Some(frontier) => {
+                    builder.scan(frontier.iter().map(Permutation::table_index), level)
+                }
+                None => builder.scan(builder.indices(0..builder.table.len()), level),
+            },
+        )
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+impl<'a, I: Inner, const ELEMENTS: usize> SolveContext<I, ELEMENTS, &'a [u8]> {
+    /// Use a solver which was written with `write_to` without copying its table.
+    /// The moves must be the moves the solver was created with, though they may be in a different order.
+    /// ```
+    /// use importunate::{Permutation, solver::SolveContext};
+    /// let moves = vec![Permutation::<u8, 4>::rotate_right(), Permutation::<u8, 4>::reverse()];
+    /// let mut bytes = vec![];
+    /// SolveContext::new(moves.clone()).write_to(&mut bytes).unwrap();
+    ///
+    /// let context = SolveContext::from_bytes(&bytes, moves).unwrap();
+    /// assert_eq!(context.number_solvable, 8);
+    /// ```
+    /// # Errors
+    ///
+    /// Returns an error with kind `InvalidData` if the bytes are not a saved solver for these moves,
+    /// or `UnexpectedEof` if they are too short
+    pub fn from_bytes(bytes: &'a [u8], moves: Vec<Permutation<I, ELEMENTS>>) -> io::Result<Self> {
+        let mut reader = bytes;
+        let (parts, table_len) = Self::read_header(&mut reader)?;
+        let table = reader
+            .get(..table_len)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+
+        Self::from_parts(table, parts, moves)
+    }
+}
+
+/// The number of bytes of the table each task scans when building in parallel
+#[cfg(any(test, feature = "rayon"))]
+const PARALLEL_CHUNK_BYTES: usize = 1 << 12;
 
-        let mut number_solvable = 0;
-        let current: &mut Vec<[u8; ELEMENTS]> =
-            &mut vec![Permutation::<I, ELEMENTS>::default().get_array()];
-        let next: &mut Vec<[u8; ELEMENTS]> = &mut vec![];
+/// How to find the permutations one move further from solved than `distance`
+#[derive(Clone, Copy, Debug)]
+struct Level {
+    distance: usize,
+    /// Check each unsolved permutation for a move to `distance`, instead of moving from each permutation at `distance`
+    pull: bool,
+    /// Return the permutations which are found, instead of marking them in the builder's layer
+    collect: bool,
+}
+
+/// A table which can be updated from several threads, two bits at a time
+struct TableBuilder<I: Inner, const ELEMENTS: usize> {
+    table: Vec<AtomicU8>,
+    /// One bit for each permutation found at the latest distance which was not collected
+    layer: Vec<AtomicU64>,
+    /// The exact distance of each permutation, if they are being recorded
+    distances: Vec<AtomicU8>,
+    total: usize,
+    move_swaps: Vec<[u8; ELEMENTS]>,
+    phantom: PhantomData<I>,
+}
 
-        let move_swaps: Vec<_> = moves.iter().map(Permutation::swaps_array).collect();
+impl<I: Inner, const ELEMENTS: usize> TableBuilder<I, ELEMENTS> {
+    fn new(total: usize, moves: &[Permutation<I, ELEMENTS>], record_distances: bool) -> Self {
+        let distances = if record_distances {
+            let distances: Vec<_> = (0..total).map(|_| AtomicU8::new(u8::MAX)).collect();
+            distances[Permutation::<I, ELEMENTS>::default().table_index()]
+                .store(0, Ordering::Relaxed);
+            distances
+        } else {
+            vec![]
+        };
+        Self {
+            table: (0..total.div_ceil(4))
+                .map(|_| AtomicU8::new(u8::MAX))
+                .collect(),
+            layer: vec![],
+            distances,
+            total,
+            move_swaps: moves.iter().map(Permutation::swaps_array).collect(),
+            phantom: PhantomData,
+        }
+    }
+
+    fn get_bits(&self, index: usize) -> u8 {
+        let shift = (index % 4) * 2;
+        3u8 & (self.table[index / 4].load(Ordering::Relaxed) >> shift)
+    }
+
+    /// Set the bits of an unsolved permutation. Returns whether this call set them
+    fn set(&self, index: usize, moves_mod_3: u8) -> bool {
+        let shift = (index % 4) * 2;
+        let old =
+            self.table[index / 4].fetch_and(!(((!moves_mod_3) & 0b11) << shift), Ordering::Relaxed);
+        (old >> shift) & 0b11 == 0b11
+    }
 
-        let mut moves_mod_3 = 0;
-        while !current.is_empty() && number_solvable <= total {
-            for perm_arr in current.drain(..) {
-                let perm1 = Permutation::<I, ELEMENTS>::calculate_unchecked(perm_arr, |x| *x);
+    /// Empty the layer before finding permutations which will not be collected
+    fn clear_layer(&mut self) {
+        if self.layer.is_empty() {
+            self.layer = (0..self.total.div_ceil(64))
+                .map(|_| AtomicU64::new(0))
+                .collect();
+        } else {
+            for word in &mut self.layer {
+                *word.get_mut() = 0;
+            }
+        }
+    }
 
-                let us: usize = perm1.0.try_into().unwrap_or_else(|_| unreachable!());
+    /// The permutations found at the latest distance which was not collected
+    fn layer(&self) -> PermutationSet<I, ELEMENTS> {
+        PermutationSet::from_words(
+            self.layer
+                .iter()
+                .map(|word| word.load(Ordering::Relaxed))
+                .collect(),
+        )
+    }
 
-                let index = us / 4usize;
-                let shift = (us % 4) * 2;
+    fn take_distances(&mut self) -> Vec<u8> {
+        core::mem::take(&mut self.distances)
+            .into_iter()
+            .map(AtomicU8::into_inner)
+            .collect()
+    }
 
-                let all_bits = vec[index];
-                let unset = (0b11 & (vec[index] >> shift)) == 0b11;
-                if unset {
-                    number_solvable += 1;
-                    let new_bits = all_bits & !(((!moves_mod_3) & 0b11) << shift);
+    /// The index of each permutation one move away from this one
+    fn neighbours(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let arr = Permutation::<I, ELEMENTS>::from_table_index(index).get_array();
+        self.move_swaps.iter().map(move |swaps| {
+            Permutation::<I, ELEMENTS>::from_array(&SolveContext::<I, ELEMENTS>::combine_arrays(
+                &arr, swaps,
+            ))
+            .table_index()
+        })
+    }
 
-                    vec[index] = new_bits;
-                    //println!("Perm {perm:03?} set {index:03} shifted {shift:01} to {moves_mod_3:02b} -> {new_bits:08b}" );
+    /// The index of each permutation in these bytes of the table
+    fn indices(&self, bytes: Range<usize>) -> Range<usize> {
+        bytes.start * 4..self.total.min(bytes.end * 4)
+    }
 
-                    for swaps in &move_swaps {
-                        let next_perm = Self::combine_arrays(&perm_arr, swaps);
-                        next.push(next_perm);
+    /// Find the permutations one move further from solved than `level.distance`, among or next to the permutations with these indices
+    fn scan(
+        &self,
+        indices: impl Iterator<Item = usize>,
+        level: Level,
+    ) -> (usize, Vec<Permutation<I, ELEMENTS>>) {
+        let moves_mod_3 = (level.distance % 3) as u8;
+        let next = ((level.distance + 1) % 3) as u8;
+        let mut count = 0;
+        let mut layer = vec![];
+        let mut found = |index: usize| {
+            count += 1;
+            if let Some(distance) = self.distances.get(index) {
+                distance.store((level.distance + 1) as u8, Ordering::Relaxed);
+            }
+            if level.collect {
+                layer.push(Permutation::<I, ELEMENTS>::from_table_index(index));
+            } else {
+                self.layer[index / 64].fetch_or(1 << (index % 64), Ordering::Relaxed);
+            }
+        };
+
+        for index in indices {
+            let bits = self.get_bits(index);
+            if level.pull {
+                // An unsolved permutation next to one set to `moves_mod_3` is exactly one move further
+                if bits == 0b11
+                    && self
+                        .neighbours(index)
+                        .any(|n| self.get_bits(n) == moves_mod_3)
+                    && self.set(index, next)
+                {
+                    found(index);
+                }
+            } else if bits == moves_mod_3 {
+                for neighbour in self.neighbours(index) {
+                    if self.get_bits(neighbour) == 0b11 && self.set(neighbour, next) {
+                        found(neighbour);
                     }
                 }
             }
+        }
+        (count, layer)
+    }
 
-            std::mem::swap(current, next);
-            moves_mod_3 += 1;
-            moves_mod_3 %= 3;
+    fn into_table(self) -> Vec<u8> {
+        self.table.into_iter().map(AtomicU8::into_inner).collect()
+    }
+}
+
+/// Visit every permutation one step beyond the frontier, recording the index of the step which reached it.
+/// Returns the first permutation which the other search has already visited
+fn search_layer<I: Inner, const ELEMENTS: usize>(
+    frontier: &mut Vec<Permutation<I, ELEMENTS>>,
+    visited: &mut BTreeMap<Permutation<I, ELEMENTS>, Option<usize>>,
+    other: &BTreeMap<Permutation<I, ELEMENTS>, Option<usize>>,
+    steps: &[Permutation<I, ELEMENTS>],
+) -> Option<Permutation<I, ELEMENTS>> {
+    let mut next = vec![];
+    for perm in frontier.drain(..) {
+        for (index, step) in steps.iter().enumerate() {
+            let combined = perm.combine(step);
+            if let Entry::Vacant(entry) = visited.entry(combined) {
+                entry.insert(Some(index));
+                if other.contains_key(&combined) {
+                    return Some(combined);
+                }
+                next.push(combined);
+            }
         }
+    }
+    *frontier = next;
+    None
+}
 
-        Self {
-            vec,
-            moves,
-            number_solvable: number_solvable
-                .try_into()
-                .unwrap_or_else(|_| unreachable!()),
+#[cfg(any(test, feature = "std"))]
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(any(test, feature = "std"))]
+fn write_usize<W: Write>(writer: &mut W, n: usize) -> io::Result<()> {
+    writer.write_all(&(n as u64).to_le_bytes())
+}
+
+#[cfg(any(test, feature = "std"))]
+fn write_count<W: Write, I: Inner>(writer: &mut W, count: I) -> io::Result<()> {
+    write_usize(writer, count.try_into().unwrap_or_else(|_| unreachable!()))
+}
+
+#[cfg(any(test, feature = "std"))]
+fn write_permutations<W: Write, I: Inner, const ELEMENTS: usize>(
+    writer: &mut W,
+    permutations: &[Permutation<I, ELEMENTS>],
+) -> io::Result<()> {
+    write_usize(writer, permutations.len())?;
+    for perm in permutations {
+        let bytes: [u8; 16] = perm.to_le_byte_array();
+        writer.write_all(&bytes[..Permutation::<I, ELEMENTS>::REQUIRED_BYTES])?;
+    }
+    Ok(())
+}
+
+#[cfg(any(test, feature = "std"))]
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(any(test, feature = "std"))]
+fn read_permutations<R: Read, I: Inner, const ELEMENTS: usize>(
+    reader: &mut R,
+) -> io::Result<Vec<Permutation<I, ELEMENTS>>> {
+    (0..read_u64(reader)?)
+        .map(|_| {
+            let mut bytes = [0u8; 16];
+            reader.read_exact(&mut bytes[..Permutation::<I, ELEMENTS>::REQUIRED_BYTES])?;
+            Permutation::try_from_le_byte_array(&bytes)
+                .ok_or_else(|| invalid_data("Saved permutation is out of range"))
+        })
+        .collect()
+}
+
+#[cfg(any(test, all(feature = "serde", feature = "std")))]
+mod serialization {
+    use serde::{Deserialize, Serialize};
+
+    use super::{SavedParts, SolveContext};
+    use crate::{inner::Inner, Permutation};
+
+    #[derive(Serialize)]
+    #[serde(rename = "SolveContext")]
+    struct SolveContextRef<'a, I: Inner, const ELEMENTS: usize> {
+        elements: usize,
+        moves: &'a [Permutation<I, ELEMENTS>],
+        number_solvable: u64,
+        distance_histogram: Vec<u64>,
+        antipodes: &'a [Permutation<I, ELEMENTS>],
+        #[serde(with = "serde_bytes")]
+        table: &'a [u8],
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "SolveContext")]
+    struct SolveContextData<I: Inner, const ELEMENTS: usize> {
+        elements: usize,
+        moves: Vec<Permutation<I, ELEMENTS>>,
+        number_solvable: u64,
+        distance_histogram: Vec<u64>,
+        antipodes: Vec<Permutation<I, ELEMENTS>>,
+        #[serde(with = "serde_bytes")]
+        table: Vec<u8>,
+    }
+
+    fn to_u64<I: Inner>(count: I) -> u64 {
+        let count: usize = count.try_into().unwrap_or_else(|_| unreachable!());
+        count as u64
+    }
+
+    impl<I: Inner + Serialize, const ELEMENTS: usize, T: AsRef<[u8]>> Serialize
+        for SolveContext<I, ELEMENTS, T>
+    {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            SolveContextRef {
+                elements: ELEMENTS,
+                moves: &self.moves,
+                number_solvable: to_u64(self.number_solvable),
+                distance_histogram: self
+                    .distance_histogram
+                    .iter()
+                    .copied()
+                    .map(to_u64)
+                    .collect(),
+                antipodes: &self.antipodes,
+                table: self.table.as_ref(),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de, I: Inner + Deserialize<'de>, const ELEMENTS: usize> Deserialize<'de>
+        for SolveContext<I, ELEMENTS>
+    {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let data = SolveContextData::<I, ELEMENTS>::deserialize(deserializer)?;
+            if data.elements != ELEMENTS {
+                return Err(serde::de::Error::custom(
+                    "SolveContext has a different number of elements",
+                ));
+            }
+            let moves = data.moves.clone();
+            let parts = SavedParts {
+                moves: data.moves,
+                number_solvable: data.number_solvable,
+                distance_histogram: data.distance_histogram,
+                antipodes: data.antipodes,
+            };
+            Self::from_parts(data.table, parts, moves).map_err(serde::de::Error::custom)
         }
     }
 }
@@ -138,7 +1111,9 @@ mod tests {
     use itertools::Itertools;
 
     use super::SolveContext;
-    use crate::{Inner, Permutation};
+    use crate::{
+        cayley_table::CayleyTable, directed_solver::DirectedSolveContext, Inner, Permutation,
+    };
 
     fn head_swaps<I: Inner, const ELEMENTS: usize>(
     ) -> impl Iterator<Item = Permutation<I, ELEMENTS>> {
@@ -161,6 +1136,368 @@ mod tests {
     pub fn test_solve5() {
         test_solve::<u8, 5>();
     }
+
+    #[test]
+    pub fn test_solve_with_table() {
+        let context = SolveContext::<u8, 5>::new(head_swaps().collect_vec());
+        let table = CayleyTable::<u8, 5>::new();
+        for perm in Permutation::<u8, 5>::all() {
+            assert_eq!(context.solve(perm), context.solve_with_table(perm, &table));
+        }
+    }
+    #[test]
+    pub fn test_write_read() {
+        let moves = head_swaps::<u16, 6>().collect_vec();
+        let context = SolveContext::new(moves.clone());
+        let mut bytes = vec![];
+        context.write_to(&mut bytes).unwrap();
+        let histogram_len = 8 + 8 * context.distance_histogram().len();
+        let antipodes_len = 8 + 2 * context.antipodes().len();
+        assert_eq!(
+            bytes.len(),
+            10 + (8 + 2 * 6) + 8 + histogram_len + antipodes_len + (8 + 180)
+        );
+
+        let reversed = moves.iter().rev().copied().collect_vec();
+        let read = SolveContext::read_from(bytes.as_slice(), reversed).unwrap();
+        assert_eq!(read, context);
+
+        let error = SolveContext::read_from(bytes.as_slice(), moves[..4].to_vec()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        let error = SolveContext::<u16, 7>::read_from(bytes.as_slice(), head_swaps().collect())
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        let mut corrupted = bytes.clone();
+        corrupted[0] = b'X';
+        let error = SolveContext::read_from(corrupted.as_slice(), moves.clone()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        let error = SolveContext::read_from(&bytes[..bytes.len() - 1], moves.clone()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        let mut huge_table = bytes.clone();
+        let table_len_start = bytes.len() - 180 - 8;
+        huge_table[table_len_start..table_len_start + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        let error = SolveContext::read_from(huge_table.as_slice(), moves.clone()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        let error = SolveContext::from_bytes(&huge_table, moves.clone()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        let borrowed = SolveContext::from_bytes(&bytes, moves.clone()).unwrap();
+        assert_eq!(borrowed.number_solvable, context.number_solvable);
+        for perm in Permutation::<u16, 6>::all() {
+            assert_eq!(borrowed.solve(perm), context.solve(perm));
+        }
+        let mut rewritten = vec![];
+        borrowed.write_to(&mut rewritten).unwrap();
+        assert_eq!(rewritten, bytes);
+
+        let error = SolveContext::from_bytes(&bytes[..bytes.len() - 1], moves).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    pub fn test_debug() {
+        let context = SolveContext::<u8, 4>::new(vec![Permutation::rotate_right()]);
+        assert_eq!(
+            format!("{context:?}"),
+            "SolveContext { table_len: 6, number_solvable: 4, moves: [Permutation(17), Permutation(23)], \
+             distance_histogram: [1, 2, 1], antipodes_len: 1, distances_len: None }"
+        );
+    }
+
+    #[test]
+    pub fn test_corrupted_table() {
+        let moves = head_swaps::<u8, 5>().collect_vec();
+        let mut bytes = vec![];
+        SolveContext::new(moves.clone())
+            .write_to(&mut bytes)
+            .unwrap();
+        let table_start = bytes.len() - 30;
+
+        for fill in [0u8, 0b0110_0100, 0b1001_0010, 0xAA] {
+            let mut corrupted = bytes.clone();
+            for (i, byte) in corrupted[table_start..].iter_mut().enumerate() {
+                *byte = fill.rotate_left(i as u32 * 2);
+            }
+            let context = SolveContext::from_bytes(&corrupted, moves.clone()).unwrap();
+            for perm in Permutation::<u8, 5>::all() {
+                if let Some(solution) = context.solve(perm) {
+                    assert!(context.is_path(perm, &solution, Permutation::default()));
+                }
+                assert!(context.distance(perm) <= Some(context.max_distance()));
+                assert!(context
+                    .all_shortest_solutions(perm)
+                    .all(|solution| context.is_path(perm, &solution, Permutation::default())));
+                let _ = context.count_shortest_solutions(perm);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_ser_de() {
+        use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+        let swap = Permutation::<u8, 3>::calculate_unchecked([1, 0, 2], |&x| x);
+        let context = SolveContext::new(vec![swap]);
+        let table: &'static [u8] = context.table.clone().leak();
+
+        let tokens = |elements| {
+            [
+                Token::Struct {
+                    name: "SolveContext",
+                    len: 6,
+                },
+                Token::Str("elements"),
+                Token::U64(elements),
+                Token::Str("moves"),
+                Token::Seq { len: Some(1) },
+                Token::U8(swap.inner()),
+                Token::SeqEnd,
+                Token::Str("number_solvable"),
+                Token::U64(2),
+                Token::Str("distance_histogram"),
+                Token::Seq { len: Some(2) },
+                Token::U64(1),
+                Token::U64(1),
+                Token::SeqEnd,
+                Token::Str("antipodes"),
+                Token::Seq { len: Some(1) },
+                Token::U8(swap.inner()),
+                Token::SeqEnd,
+                Token::Str("table"),
+                Token::Bytes(table),
+                Token::StructEnd,
+            ]
+        };
+
+        assert_tokens(&context, &tokens(3));
+        assert_de_tokens_error::<SolveContext<u8, 3>>(
+            &tokens(4),
+            "SolveContext has a different number of elements",
+        );
+
+        // Tables written as a sequence of numbers can still be read
+        let mut sequence = tokens(3).to_vec();
+        sequence.splice(
+            sequence.len() - 2..sequence.len() - 1,
+            [
+                Token::Seq { len: Some(2) },
+                Token::U8(table[0]),
+                Token::U8(table[1]),
+                Token::SeqEnd,
+            ],
+        );
+        assert_de_tokens(&context, &sequence);
+    }
+
+    #[test]
+    pub fn test_distances() {
+        let context = SolveContext::<u16, 7>::new(head_swaps().collect_vec());
+        let mut histogram = vec![0u16; context.max_distance() + 1];
+        for perm in Permutation::<u16, 7>::all() {
+            let distance = context.distance(perm).unwrap();
+            assert_eq!(distance, context.solve(perm).unwrap().len());
+            histogram[distance] += 1;
+            assert_eq!(
+                context.antipodes().contains(&perm),
+                distance == context.max_distance()
+            );
+        }
+        assert_eq!(context.distance_histogram(), histogram);
+        // Sorting with swaps of the first element takes at most 3(n-1)/2 moves
+        assert_eq!(context.max_distance(), 9);
+
+        let context = SolveContext::<u8, 5>::new(vec![Permutation::rotate_right()]);
+        assert_eq!(context.distance_histogram(), [1, 2, 2]);
+        assert_eq!(context.distance(Permutation::reverse()), None);
+    }
+
+    #[test]
+    pub fn test_all_shortest_solutions() {
+        let moves = vec![
+            Permutation::<u8, 5>::rotate_right(),
+            Permutation::reverse(),
+            Permutation::interleave(2),
+        ];
+        let context = SolveContext::<u8, 5>::new(moves);
+        for perm in Permutation::<u8, 5>::all() {
+            let solutions = context.all_shortest_solutions(perm).collect_vec();
+            let Some(distance) = context.distance(perm) else {
+                assert!(solutions.is_empty());
+                assert_eq!(context.count_shortest_solutions(perm), None);
+                continue;
+            };
+
+            assert_eq!(solutions.first(), context.solve(perm).as_ref());
+            assert_eq!(
+                context.count_shortest_solutions(perm),
+                Some(solutions.len() as u128)
+            );
+            assert!(solutions.iter().all_unique());
+            for solution in &solutions {
+                assert_eq!(solution.len(), distance);
+                assert!(solution.iter().fold(perm, |p, m| p.combine(m)).is_default());
+            }
+
+            // Every shortest solution starts with a move to a permutation one step closer to solved
+            let first_moves = context
+                .moves()
+                .iter()
+                .filter(|m| context.distance(perm.combine(m)) == distance.checked_sub(1))
+                .count();
+            assert_eq!(
+                solutions.iter().filter_map(|s| s.first()).dedup().count(),
+                first_moves
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_path() {
+        let context = SolveContext::<u8, 5>::new(head_swaps().take(2).collect_vec());
+        let identity = Permutation::<u8, 5>::default();
+        for perm in Permutation::<u8, 5>::all() {
+            let generated = context.generate_from_identity(perm);
+            assert_eq!(
+                generated.as_ref().map(Vec::len),
+                context.distance(perm.invert())
+            );
+            if let Some(generated) = &generated {
+                assert!(context.is_path(identity, generated, perm));
+            }
+
+            let solved = context.solve_to_identity(perm);
+            assert_eq!(solved, context.solve(perm));
+            if let Some(solved) = &solved {
+                assert!(context.is_path(perm, solved, identity));
+            }
+        }
+
+        let all = Permutation::<u8, 5>::all().collect_vec();
+        for (&from, &to) in all.iter().cartesian_product(&all).step_by(7) {
+            let path = context.path(from, to);
+            assert_eq!(
+                path.as_ref().map(Vec::len),
+                context.distance(to.invert().combine(&from))
+            );
+            if let Some(path) = &path {
+                assert!(context.is_path(from, path, to));
+                assert!(!path.is_empty() || from == to);
+            }
+        }
+
+        assert!(!context.is_path(identity, &[Permutation::reverse()], Permutation::reverse()));
+        assert!(!context.is_path(identity, context.moves(), identity));
+    }
+
+    #[test]
+    pub fn test_solve_once() {
+        let moves = head_swaps::<u16, 7>().take(4).collect_vec();
+        let context = SolveContext::new(moves.clone());
+        for perm in Permutation::<u16, 7>::all().step_by(11) {
+            let solution = SolveContext::solve_once(moves.clone(), perm);
+            assert_eq!(solution.as_ref().map(Vec::len), context.distance(perm));
+            if let Some(solution) = solution {
+                assert!(context.is_path(perm, &solution, Permutation::default()));
+            }
+        }
+
+        // Too many elements for a table
+        let moves = head_swaps::<u64, 14>().collect_vec();
+        let perm = moves
+            .iter()
+            .take(6)
+            .fold(Permutation::default(), |p, m| p.combine(m));
+        let solution = SolveContext::solve_once(moves, perm).unwrap();
+        assert_eq!(solution.len(), 6);
+        assert!(solution.iter().fold(perm, |p, m| p.combine(m)).is_default());
+    }
+
+    #[test]
+    pub fn test_new_parallel() {
+        assert_eq!(
+            SolveContext::<u8, 5>::new_parallel(head_swaps().collect_vec()),
+            SolveContext::new(head_swaps().collect_vec())
+        );
+        assert_eq!(
+            SolveContext::<u16, 8>::new_parallel(head_swaps().collect_vec()),
+            SolveContext::new(head_swaps().collect_vec())
+        );
+
+        let moves = vec![
+            Permutation::<u16, 7>::rotate_right(),
+            Permutation::reverse(),
+            Permutation::interleave(3),
+        ];
+        assert_eq!(
+            SolveContext::new_parallel(moves.clone()),
+            SolveContext::new(moves)
+        );
+
+        // Few solvable permutations, and many at the maximum distance
+        let moves = vec![Permutation::<u16, 8>::rotate_right()];
+        assert_eq!(
+            SolveContext::new_parallel(moves.clone()),
+            SolveContext::new(moves)
+        );
+    }
+
+    #[test]
+    pub fn test_new_matches_distances() {
+        type Perm = Permutation<u16, 7>;
+        let three_cycle = |i: usize| {
+            let mut arr = Perm::default().get_array();
+            arr[0] = 1;
+            arr[1] = i as u8;
+            arr[i] = 0;
+            Perm::calculate_unchecked(arr, |&x| x)
+        };
+        for moves in [
+            vec![Perm::rotate_right(), Perm::reverse(), Perm::interleave(3)],
+            head_swaps().collect_vec(),
+            // There are too many antipodes to collect while building
+            (0..7)
+                .tuple_combinations()
+                .map(|(i, j)| {
+                    let mut arr = Perm::default().get_array();
+                    arr.swap(i, j);
+                    Perm::calculate_unchecked(arr, |&x| x)
+                })
+                .collect_vec(),
+            // These do not generate every permutation
+            (2..7).map(three_cycle).collect_vec(),
+            vec![three_cycle(2), three_cycle(3)],
+            vec![Perm::rotate_right(), Perm::reverse()],
+        ] {
+            let context = SolveContext::new(moves.clone());
+            let with_distances = SolveContext::new_with_distances(moves.clone());
+            let inverses = moves.iter().map(Permutation::invert);
+            let exact = DirectedSolveContext::new(moves.iter().copied().chain(inverses).collect());
+
+            let mut histogram = vec![0u16; context.max_distance() + 1];
+            let mut antipodes = vec![];
+            for perm in Perm::all() {
+                let distance = exact.distance(perm);
+                assert_eq!(context.distance(perm), distance);
+                assert_eq!(with_distances.distance(perm), distance);
+                if let Some(distance) = distance {
+                    histogram[distance] += 1;
+                    if distance == context.max_distance() {
+                        antipodes.push(perm);
+                    }
+                }
+            }
+            assert_eq!(context.distance_histogram(), histogram);
+            assert_eq!(context.antipodes(), antipodes);
+            assert_eq!(context.number_solvable, exact.number_solvable);
+            assert_eq!(with_distances.table, context.table);
+            assert_eq!(with_distances.antipodes(), context.antipodes());
+            assert_eq!(SolveContext::new_parallel(moves), context);
+        }
+    }
+
     #[test]
     pub fn test_solve6() {
         test_solve::<u16, 6>();
@@ -209,7 +1546,7 @@ mod tests {
         );
 
         let total = check_solutions(&context);
-        assert_eq!(total, claimed_solvable)
+        assert_eq!(total, claimed_solvable);
     }
 
     #[test]
@@ -260,7 +1597,7 @@ mod tests {
         context: &SolveContext<I, ELEMENTS>,
     ) -> usize {
         let mut count = 0;
-        for bits in &context.vec {
+        for bits in &context.table {
             for shift in [0, 2, 4, 6] {
                 if bits >> shift & 0b11 != 0b11 {
                     count += 1;
@@ -0,0 +1,243 @@
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::{inner::Inner, Permutation};
+
+/// A stabilizer chain for the group generated by a set of permutations, built with the Schreier-Sims algorithm.
+///
+/// This answers questions about the group without listing its elements,
+/// so it works for groups far too large to search - up to 34 elements.
+///
+/// Permutations are treated as the arrays returned by `get_array`.
+/// ```
+/// use importunate::{Permutation, inner::Inner, stabilizer_chain::StabilizerChain};
+/// let rotate = Permutation::<u128, 30>::rotate_right();
+/// let swap = Permutation::<u128, 30>::calculate_unchecked(core::array::from_fn(|i| [1, 0].get(i).copied().unwrap_or(i as u8)), |&x| x);
+///
+/// // A rotation and a single swap generate every permutation
+/// let chain = StabilizerChain::new(&[rotate, swap]);
+/// assert_eq!(chain.order(), u128::get_factorial(30));
+/// assert!(chain.contains(&Permutation::<u128, 30>::interleave(3)));
+///
+/// // A rotation on its own only generates rotations
+/// let chain = StabilizerChain::new(&[rotate]);
+/// assert_eq!(chain.order(), 30);
+/// assert!(!chain.contains(&Permutation::<u128, 30>::interleave(3)));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct StabilizerChain<I: Inner, const ELEMENTS: usize> {
+    /// Level `k` describes the permutations which fix every index before `k`
+    levels: Vec<Level<ELEMENTS>>,
+    phantom: PhantomData<I>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Level<const ELEMENTS: usize> {
+    /// Generators of this level's group which are not in the next level
+    generators: Vec<[u8; ELEMENTS]>,
+    /// For each index `x` in the orbit of `k`, a permutation which moves index `k` to `x`
+    transversal: Vec<Option<[u8; ELEMENTS]>>,
+}
+
+/// The permutation made by doing `rhs` and then `lhs`, applied to indices
+fn compose<const ELEMENTS: usize>(lhs: &[u8; ELEMENTS], rhs: &[u8; ELEMENTS]) -> [u8; ELEMENTS] {
+    rhs.map(|x| lhs[usize::from(x)])
+}
+
+fn invert<const ELEMENTS: usize>(arr: &[u8; ELEMENTS]) -> [u8; ELEMENTS] {
+    let mut inverse = [0; ELEMENTS];
+    for (index, &x) in arr.iter().enumerate() {
+        inverse[usize::from(x)] = index as u8;
+    }
+    inverse
+}
+
+impl<I: Inner, const ELEMENTS: usize> StabilizerChain<I, ELEMENTS> {
+    /// Build the stabilizer chain for the group generated by these permutations
+    pub fn new(generators: &[Permutation<I, ELEMENTS>]) -> Self {
+        debug_assert!(ELEMENTS <= I::MAX_ELEMENTS);
+        let levels = (0..ELEMENTS)
+            .map(|k| {
+                let mut transversal = vec![None; ELEMENTS];
+                transversal[k] = Some(Permutation::<I, ELEMENTS>::DEFAULT_ARRAY);
+                Level {
+                    generators: vec![],
+                    transversal,
+                }
+            })
+            .collect();
+        let mut chain = Self {
+            levels,
+            phantom: PhantomData,
+        };
+
+        for generator in generators {
+            chain.extend(0, generator.get_array());
+        }
+        chain
+    }
+
+    /// Does the permutation belong to the group at or below level `k`
+    fn sift(&self, k: usize, mut arr: [u8; ELEMENTS]) -> bool {
+        for (index, level) in self.levels.iter().enumerate().skip(k) {
+            let Some(representative) = &level.transversal[usize::from(arr[index])] else {
+                return false;
+            };
+            arr = compose(&invert(representative), &arr);
+        }
+        true
+    }
+
+    /// Add a permutation which fixes every index before `k` to level `k`
+    fn extend(&mut self, k: usize, arr: [u8; ELEMENTS]) {
+        if self.sift(k, arr) {
+            return;
+        }
+        self.levels[k].generators.push(arr);
+        let orbit: Vec<[u8; ELEMENTS]> = self.levels[k]
+            .transversal
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        for representative in orbit {
+            self.update(k, compose(&arr, &representative));
+        }
+    }
+
+    /// Add a permutation which fixes every index before `k` to the transversal of level `k`
+    fn update(&mut self, k: usize, arr: [u8; ELEMENTS]) {
+        let x = usize::from(arr[k]);
+        if let Some(representative) = &self.levels[k].transversal[x] {
+            let schreier_generator = compose(&invert(representative), &arr);
+            if k + 1 < ELEMENTS {
+                self.extend(k + 1, schreier_generator);
+            }
+            return;
+        }
+
+        self.levels[k].transversal[x] = Some(arr);
+        let generators = self.levels[k].generators.clone();
+        for generator in generators {
+            self.update(k, compose(&generator, &arr));
+        }
+    }
+
+    /// The number of permutations in the group
+    #[must_use]
+    pub fn order(&self) -> I {
+        self.levels
+            .iter()
+            .map(|level| I::from(level.transversal.iter().flatten().count() as u8))
+            .product()
+    }
+
+    /// Is this permutation in the group
+    #[must_use]
+    pub fn contains(&self, perm: &Permutation<I, ELEMENTS>) -> bool {
+        self.sift(0, perm.get_array())
+    }
+
+    /// Get the permutation of the group with this rank, from `0` to `order() - 1`.
+    /// Each rank gives a different permutation. Returns `None` if the rank is too large
+    #[must_use]
+    pub fn element_at(&self, mut rank: I) -> Option<Permutation<I, ELEMENTS>> {
+        let perm = self.element_from_choices(|orbit_len| {
+            let (div, rem) = rank.div_rem(&I::from(orbit_len as u8));
+            rank = div;
+            rem.try_into().unwrap_or_else(|_| unreachable!())
+        });
+        rank.is_zero().then_some(perm)
+    }
+
+    /// Get a uniformly random permutation of the group
+    #[cfg(any(test, feature = "rand"))]
+    pub fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Permutation<I, ELEMENTS> {
+        self.element_from_choices(|orbit_len| rng.gen_range(0..orbit_len))
+    }
+
+    /// Make a permutation by choosing a transversal element from each level
+    fn element_from_choices(
+        &self,
+        mut choose: impl FnMut(usize) -> usize,
+    ) -> Permutation<I, ELEMENTS> {
+        let mut arr = Permutation::<I, ELEMENTS>::DEFAULT_ARRAY;
+        for level in &self.levels {
+            let orbit_len = level.transversal.iter().flatten().count();
+            let choice = choose(orbit_len);
+            if let Some(representative) = level.transversal.iter().flatten().nth(choice) {
+                arr = compose(&arr, representative);
+            }
+        }
+        Permutation::from_array(&arr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::StabilizerChain;
+    use crate::{group::Group, inner::Inner, Permutation};
+
+    fn check<const ELEMENTS: usize>(generators: &[Permutation<u16, ELEMENTS>]) {
+        let group = Group::generate(generators);
+        let chain = StabilizerChain::new(generators);
+        assert_eq!(usize::from(chain.order()), group.order());
+        for perm in Permutation::<u16, ELEMENTS>::all() {
+            assert_eq!(chain.contains(&perm), group.contains(&perm));
+        }
+
+        let elements = (0..chain.order())
+            .map(|rank| chain.element_at(rank).unwrap())
+            .collect_vec();
+        assert!(elements.iter().all_unique());
+        assert!(elements.iter().all(|perm| group.contains(perm)));
+        assert_eq!(chain.element_at(chain.order()), None);
+    }
+
+    #[test]
+    pub fn test_small_groups() {
+        check::<6>(&[]);
+        check::<6>(&[Permutation::rotate_right()]);
+        check::<6>(&[Permutation::rotate_right(), Permutation::reverse()]);
+        check::<6>(&[Permutation::interleave(2), Permutation::interleave(3)]);
+        check::<7>(&[Permutation::interleave(2), Permutation::rotate_left()]);
+        check::<7>(&[
+            Permutation::calculate_unchecked([1, 2, 0, 3, 4, 5, 6], |&x| x),
+            Permutation::calculate_unchecked([0, 1, 2, 4, 5, 6, 3], |&x| x),
+        ]);
+    }
+
+    #[test]
+    pub fn test_large_groups() {
+        type Perm = Permutation<u128, 34>;
+        let swap = Perm::calculate_unchecked(
+            core::array::from_fn(|i| [1, 0].get(i).copied().unwrap_or(i as u8)),
+            |&x| x,
+        );
+        let symmetric = StabilizerChain::new(&[Perm::rotate_right(), swap]);
+        assert_eq!(symmetric.order(), u128::get_factorial(34));
+
+        let three_cycle = Perm::calculate_unchecked(
+            core::array::from_fn(|i| [1, 2, 0].get(i).copied().unwrap_or(i as u8)),
+            |&x| x,
+        );
+        let shift = Perm::calculate_unchecked(
+            core::array::from_fn(|i| if i == 0 { 0 } else { (i % 33 + 1) as u8 }),
+            |&x| x,
+        );
+        let alternating = StabilizerChain::new(&[three_cycle, shift]);
+        assert_eq!(alternating.order(), u128::get_factorial(34) / 2);
+
+        let mut rng = StdRng::seed_from_u64(123);
+        for _ in 0..100 {
+            let perm = symmetric.sample(&mut rng);
+            let even = perm.swaps().filter(|&swap| swap != 0).count() % 2 == 0;
+            assert_eq!(alternating.contains(&perm), even);
+            assert!(alternating.contains(&alternating.sample(&mut rng)));
+        }
+    }
+}
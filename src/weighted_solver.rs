@@ -0,0 +1,191 @@
+use alloc::{collections::BinaryHeap, vec, vec::Vec};
+use core::cmp::Reverse;
+
+use crate::{inner::Inner, permutation_set::PermutationSet, Permutation};
+
+/// Can be used to solve a permutation when moves have different costs,
+/// finding the combination of moves with the lowest total cost that leads to it.
+///
+/// This stores the total cost of every permutation so the finished solver takes sixteen times the memory of a `SolveContext`.
+/// While it is being built it also marks which permutations are settled, using half the memory of a `SolveContext`,
+/// and queues each permutation when a cheaper way to solve it is found.
+/// If every move has the same cost, use a `SolveContext` instead.
+/// ```
+/// use importunate::{Permutation, weighted_solver::WeightedSolveContext};
+/// let rotate = Permutation::<u8, 4>::rotate_right();
+/// let reverse = Permutation::<u8, 4>::reverse();
+/// let context = WeightedSolveContext::new(vec![(rotate, 1), (reverse, 5)]);
+///
+/// // Rotating twice is cheaper than reversing and rotating
+/// let perm = rotate.combine(&rotate);
+/// assert_eq!(context.cost(perm), Some(2));
+/// assert_eq!(context.solve(perm), Some(vec![rotate.invert(), rotate.invert()]));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightedSolveContext<I: Inner, const ELEMENTS: usize> {
+    /// The total cost of solving each permutation, or `u32::MAX` if it cannot be solved
+    costs: Vec<u32>,
+
+    /// The total number of solvable permutations
+    pub number_solvable: I,
+
+    moves: Vec<(Permutation<I, ELEMENTS>, u32)>,
+}
+
+impl<I: Inner, const ELEMENTS: usize> WeightedSolveContext<I, ELEMENTS> {
+    /// Create a new solver from a fixed set of moves and their costs.
+    /// This will also use the inverses of those moves, with the same costs.
+    /// If a move is given more than once, the lowest cost is used
+    /// # Panics
+    ///
+    /// This will panic if the number of possible solutions is greater than `usize::MAX`,
+    /// if any move costs zero or if the cost of solving any permutation does not fit in a `u32`
+    #[must_use]
+    pub fn new(mut moves: Vec<(Permutation<I, ELEMENTS>, u32)>) -> Self {
+        let total = Permutation::<I, ELEMENTS>::table_len();
+        assert!(
+            moves.iter().all(|&(_, cost)| cost > 0),
+            "Moves must have a positive cost"
+        );
+
+        for (m, cost) in moves.clone() {
+            moves.push((m.invert(), cost));
+        }
+        moves.sort();
+        moves.dedup_by_key(|(m, _)| *m);
+
+        // The lowest cost found so far, which is final once the permutation is settled
+        let mut costs = vec![u32::MAX; total];
+        let mut settled = PermutationSet::<I, ELEMENTS>::new();
+        let mut number_solvable = 0usize;
+        let mut queue = BinaryHeap::new();
+        costs[Permutation::<I, ELEMENTS>::default().table_index()] = 0;
+        queue.push(Reverse((0u32, Permutation::<I, ELEMENTS>::default())));
+
+        while let Some(Reverse((cost, perm))) = queue.pop() {
+            // A permutation is queued again each time its cost improves, so skip the older entries
+            if !settled.insert(perm) {
+                continue;
+            }
+            number_solvable += 1;
+
+            for &(m, move_cost) in &moves {
+                let next = perm.combine(&m);
+                if settled.contains(&next) {
+                    continue;
+                }
+                let Some(next_cost) = cost.checked_add(move_cost).filter(|&c| c < u32::MAX) else {
+                    panic!("The cost of solving a permutation is too large");
+                };
+                let next_index = next.table_index();
+                if next_cost < costs[next_index] {
+                    costs[next_index] = next_cost;
+                    queue.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+
+        Self {
+            costs,
+            number_solvable: I::try_from(number_solvable).unwrap_or_else(|_| unreachable!()),
+            moves,
+        }
+    }
+
+    /// The moves this solver uses and their costs, including their inverses
+    pub fn moves(&self) -> &[(Permutation<I, ELEMENTS>, u32)] {
+        &self.moves
+    }
+
+    /// The lowest total cost of moves which solve this permutation, or `None` if it cannot be solved
+    #[must_use]
+    pub fn cost(&self, perm: Permutation<I, ELEMENTS>) -> Option<u32> {
+        let cost = self.costs[perm.table_index()];
+        (cost != u32::MAX).then_some(cost)
+    }
+
+    /// Try to Deconstruct the inverse of this permutation into the sequence of moves with the lowest total cost
+    pub fn solve(
+        &self,
+        mut perm: Permutation<I, ELEMENTS>,
+    ) -> Option<Vec<Permutation<I, ELEMENTS>>> {
+        let mut cost = self.cost(perm)?;
+        let mut result = vec![];
+
+        'outer: while cost > 0 {
+            for &(m, move_cost) in &self.moves {
+                let next = perm.combine(&m);
+                if self.cost(next).and_then(|c| c.checked_add(move_cost)) == Some(cost) {
+                    perm = next;
+                    cost -= move_cost;
+                    result.push(m);
+                    continue 'outer;
+                }
+            }
+
+            unreachable!()
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::WeightedSolveContext;
+    use crate::{solver::SolveContext, Permutation};
+
+    type Perm = Permutation<u8, 5>;
+
+    #[test]
+    pub fn test_equal_costs() {
+        let moves = vec![Perm::rotate_right(), Perm::reverse(), Perm::interleave(2)];
+        let unweighted = SolveContext::new(moves.clone());
+        let weighted = WeightedSolveContext::new(moves.into_iter().map(|m| (m, 3)).collect());
+        assert_eq!(weighted.number_solvable, unweighted.number_solvable);
+        for perm in Perm::all() {
+            assert_eq!(
+                weighted.cost(perm),
+                unweighted.distance(perm).map(|d| d as u32 * 3)
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_weighted() {
+        let swap = |a: usize, b: usize| {
+            let mut arr = [0, 1, 2, 3, 4];
+            arr.swap(a, b);
+            Perm::calculate_unchecked(arr, |&x| x)
+        };
+        let moves = (0..5)
+            .tuple_combinations()
+            .map(|(a, b)| (swap(a, b), (b - a) as u32 * (b - a) as u32))
+            .collect_vec();
+        let context = WeightedSolveContext::new(moves);
+        assert_eq!(usize::from(context.number_solvable), 120);
+        assert_eq!(context.cost(Perm::default()), Some(0));
+
+        for perm in Perm::all() {
+            let cost = context.cost(perm).unwrap();
+            let solution = context.solve(perm).unwrap();
+
+            let mut solved = perm;
+            let mut solution_cost = 0;
+            for m in &solution {
+                solved = solved.combine(m);
+                let (_, move_cost) = context.moves().iter().find(|(x, _)| x == m).unwrap();
+                solution_cost += move_cost;
+            }
+            assert!(solved.is_default());
+            assert_eq!(solution_cost, cost);
+
+            // No move leads to a permutation which could be solved more cheaply
+            for &(m, move_cost) in context.moves() {
+                assert!(cost <= move_cost + context.cost(perm.combine(&m)).unwrap());
+            }
+        }
+    }
+}
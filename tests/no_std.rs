@@ -0,0 +1,24 @@
+use std::process::Command;
+
+/// Build the library with these features, in its own target directory so it does not wait for the lock on this one
+fn build(features: &[&str]) {
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "--no-default-features", "--features"])
+        .arg(features.join(","))
+        .arg("--manifest-path")
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .arg("--target-dir")
+        .arg(concat!(env!("CARGO_TARGET_TMPDIR"), "/no_std"))
+        .status()
+        .unwrap();
+    assert!(
+        status.success(),
+        "Failed to build with features {features:?}"
+    );
+}
+
+#[test]
+fn test_build_without_std() {
+    build(&[]);
+    build(&["alloc"]);
+}